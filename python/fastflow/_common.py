@@ -53,7 +53,7 @@ def check_graph(g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]) -> N
         raise ValueError(msg)
 
 
-def check_planelike(vset: AbstractSet[V], oset: AbstractSet[V], plike: Mapping[V, P]) -> None:
+def check_planelike(vset: AbstractSet[V], oset: AbstractSet[V], plike: Mapping[V, object]) -> None:
     r"""Check if measurement config. is valid.
 
     Raises
@@ -135,6 +135,41 @@ class IndexMap(Generic[V]):
         """
         return {self.encode(k): v for k, v in mapping.items()}
 
+    def encode_flow(self, f: Mapping[V, V]) -> dict[int, int]:
+        """Encode MBQC flow.
+
+        Returns
+        -------
+        Transformed flow.
+        """
+        return {self.encode(i): self.encode(j) for i, j in f.items()}
+
+    def encode_gflow(self, f: Mapping[V, AbstractSet[V]]) -> dict[int, set[int]]:
+        """Encode MBQC gflow.
+
+        Returns
+        -------
+        Transformed gflow.
+        """
+        return {self.encode(i): self.encode_set(si) for i, si in f.items()}
+
+    def encode_layer(self, layer: Mapping[V, int]) -> list[int]:
+        """Encode MBQC layer.
+
+        Returns
+        -------
+        Transformed layer as list.
+
+        Raises
+        ------
+        ValueError
+            If `layer` is not specified for exactly the registered vertices.
+        """
+        if layer.keys() != self.__v2i.keys():
+            msg = "Layer should be specified for all the vertices."
+            raise ValueError(msg)
+        return [layer[self.__i2v[i]] for i in range(len(self.__i2v))]
+
     def decode(self, i: int) -> V:
         """Decode the index.
 
@@ -0,0 +1,58 @@
+from array import array
+from collections.abc import Mapping
+
+from fastflow._impl.gflow import Plane
+from fastflow._impl.pflow import PPlane
+
+class FindStats:
+    max_row_weight: int
+    total_ones: int
+    pivot_swaps: int
+    row_xors: int
+    branch_attempts: dict[PPlane, int]
+    branch_successes: dict[PPlane, int]
+
+class GF2System:
+    neqs: int
+    def __init__(self, a: list[list[bool]]) -> None: ...
+    def add_rhs(self, b: list[bool]) -> int: ...
+    def solve(self, ieq: int) -> list[bool] | None: ...
+    def rank(self) -> int: ...
+
+def det_gf2(a: list[list[bool]]) -> bool: ...
+def invert(a: list[list[bool]]) -> list[list[bool]] | None: ...
+def nullity(a: list[list[bool]]) -> int: ...
+def solve_all_solutions(a: list[list[bool]], b: list[bool], max_free: int) -> list[list[bool]]: ...
+def solve_int(a: list[list[int]], bs: list[list[int]]) -> list[list[bool] | None]: ...
+def solve_one(a: list[list[bool]], b: list[bool]) -> list[bool] | None: ...
+def solve_sparse(
+    indptr: list[int], indices: list[int], shape: tuple[int, int], bs: list[list[bool]]
+) -> list[list[bool] | None]: ...
+def solve_with_rank(a: list[list[bool]], bs: list[list[bool]]) -> list[tuple[list[bool] | None, int]]: ...
+def is_symmetric(g: list[set[int]]) -> bool: ...
+def find_asymmetry(g: list[set[int]]) -> tuple[int, int] | None: ...
+def graph_from_edges(n: int, edges: list[tuple[int, int]], symmetric: bool = False) -> list[set[int]]: ...
+def to_edges(g: list[set[int]]) -> list[tuple[int, int]]: ...
+def layer_array(layer: list[int]) -> array[int]: ...
+def check_node_count(
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    meas: Mapping[int, Plane] | Mapping[int, PPlane],
+) -> None: ...
+def stepwise_plan(
+    f: dict[int, set[int]], layer: list[int], g: list[set[int]]
+) -> list[tuple[list[int], list[tuple[int, int]], list[tuple[int, int]]]]: ...
+def odd_neighbors_of_flow(
+    f: dict[int, set[int]], g: list[set[int]]
+) -> dict[int, set[int]]: ...
+def past(
+    node: int, f: dict[int, set[int]], layer: list[int], g: list[set[int]]
+) -> set[int]: ...
+def dump_case(
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    planes: Mapping[int, Plane] | Mapping[int, PPlane],
+) -> str: ...
+def depth_lower_bound(g: list[set[int]], oset: set[int]) -> int: ...
@@ -1 +1,17 @@
 def find(g: list[set[int]], iset: set[int], oset: set[int]) -> tuple[dict[int, int], list[int]] | None: ...
+def depth(g: list[set[int]], iset: set[int], oset: set[int]) -> int | None: ...
+def inverse(f: dict[int, int]) -> dict[int, int]: ...
+def layers(g: list[set[int]], iset: set[int], oset: set[int]) -> list[set[int]] | None: ...
+def as_gflow(f: dict[int, int], layer: list[int]) -> tuple[dict[int, set[int]], list[int]]: ...
+def check_definition(f: dict[int, int], layer: list[int], g: list[set[int]]) -> None: ...
+def dag(g: list[set[int]], iset: set[int], oset: set[int]) -> list[tuple[int, int]] | None: ...
+def exists(g: list[set[int]], iset: set[int], oset: set[int]) -> bool: ...
+def find_bounded(
+    g: list[set[int]], iset: set[int], oset: set[int], max_depth: int
+) -> tuple[dict[int, int], list[int]] | None: ...
+def find_or_blocker(
+    g: list[set[int]], iset: set[int], oset: set[int]
+) -> tuple[tuple[dict[int, int], list[int]] | None, int | None]: ...
+def verify_all(
+    f: dict[int, int], layer: list[int], g: list[set[int]], iset: set[int], oset: set[int]
+) -> list[str]: ...
@@ -1,3 +1,8 @@
+from array import array
+from collections.abc import Callable
+
+from fastflow._impl.common import FindStats
+
 class Plane:
     XY: Plane
     YZ: Plane
@@ -6,3 +11,105 @@ class Plane:
 def find(
     g: list[set[int]], iset: set[int], oset: set[int], plane: dict[int, Plane]
 ) -> tuple[dict[int, set[int]], list[int]] | None: ...
+
+def exists(g: list[set[int]], iset: set[int], oset: set[int], plane: dict[int, Plane]) -> bool: ...
+def find_min(
+    g: list[set[int]], iset: set[int], oset: set[int], plane: dict[int, Plane]
+) -> tuple[dict[int, set[int]], list[int]] | None: ...
+def find_csr(
+    g: list[set[int]], iset: set[int], oset: set[int], plane: dict[int, Plane]
+) -> tuple[array[int], array[int], list[int]] | None: ...
+def find_all(
+    g: list[set[int]], iset: set[int], oset: set[int], plane: dict[int, Plane], limit: int
+) -> list[tuple[dict[int, set[int]], list[int]]]: ...
+def find_with_forbidden_odd(
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+    forbidden: set[int],
+) -> tuple[dict[int, set[int]], list[int]] | None: ...
+def find_with_local_complements(
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+    max_ops: int,
+) -> tuple[list[int], dict[int, set[int]], list[int]] | None: ...
+def correctable(
+    g: list[set[int]],
+    iset: set[int],
+    oset_fixed: set[int],
+    u: int,
+    plane: Plane,
+) -> set[int] | None: ...
+def layer_matrix(
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+    fixed: set[int],
+) -> tuple[list[list[bool]], list[list[bool]]]: ...
+def find_from_dict(
+    adj: dict[int, set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+) -> tuple[dict[int, set[int]], dict[int, int]] | None: ...
+def find_multiplane(
+    g: list[set[int]], iset: set[int], oset: set[int], allowed: dict[int, list[Plane]]
+) -> tuple[dict[int, set[int]], list[int], dict[int, Plane]] | None: ...
+
+class FlowReport:
+    f: dict[int, set[int]]
+    layer: list[int]
+    depth: int
+    odd: dict[int, set[int]]
+    order: list[tuple[int, int]]
+    rank: dict[int, int]
+    nullity: dict[int, int]
+    stats: FindStats
+
+def find_verbose(
+    g: list[set[int]], iset: set[int], oset: set[int], plane: dict[int, Plane]
+) -> FlowReport | None: ...
+def forced_nodes(report: FlowReport) -> set[int]: ...
+def check_node_correction(
+    i: int, ci: set[int], g: list[set[int]], layer: list[int], plane: Plane
+) -> None: ...
+def is_valid_order(
+    f: dict[int, int] | dict[int, set[int]], layer: list[int], g: list[set[int]]
+) -> bool: ...
+def correction_volume(f: dict[int, int] | dict[int, set[int]], g: list[set[int]]) -> int: ...
+def verify(
+    f: dict[int, set[int]],
+    layer: list[int],
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+    tolerant: bool = False,
+    progress: Callable[[int, int], object] | None = None,
+) -> None: ...
+def verify_maximal(
+    f: dict[int, set[int]],
+    layer: list[int],
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+) -> None: ...
+def canonicalize(
+    f: dict[int, set[int]],
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+) -> tuple[dict[int, set[int]], list[int]]: ...
+def verify_corrections(
+    f: dict[int, set[int]],
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+) -> None: ...
@@ -1,3 +1,6 @@
+from fastflow._impl.common import FindStats
+from fastflow._impl.gflow import Plane
+
 class PPlane:
     XY: PPlane
     YZ: PPlane
@@ -5,7 +8,66 @@ class PPlane:
     X: PPlane
     Y: PPlane
     Z: PPlane
+    @staticmethod
+    def from_plane_angle(plane: Plane, angle: float, atol: float) -> PPlane: ...
 
 def find(
     g: list[set[int]], iset: set[int], oset: set[int], pplane: dict[int, PPlane]
 ) -> tuple[dict[int, set[int]], list[int]] | None: ...
+
+class PFlowResult:
+    f: dict[int, set[int]]
+    layer: list[int]
+    depth: int
+    branch: dict[int, PPlane]
+    def __init__(
+        self, f: dict[int, set[int]], layer: list[int], depth: int, branch: dict[int, PPlane]
+    ) -> None: ...
+
+def find_from_angles(
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+    angles: dict[int, float],
+    atol: float,
+) -> tuple[dict[int, set[int]], list[int]] | None: ...
+def find_verbose(
+    g: list[set[int]], iset: set[int], oset: set[int], pplane: dict[int, PPlane]
+) -> PFlowResult | None: ...
+def find_with_stats(
+    g: list[set[int]], iset: set[int], oset: set[int], pplane: dict[int, PPlane]
+) -> tuple[tuple[dict[int, set[int]], list[int]] | None, FindStats]: ...
+
+class PFlowWorkspace:
+    def __init__(self) -> None: ...
+    def find_with(
+        self, g: list[set[int]], iset: set[int], oset: set[int], pplane: dict[int, PPlane]
+    ) -> tuple[dict[int, set[int]], list[int]] | None: ...
+
+def verify(
+    f: dict[int, set[int]],
+    layer: list[int],
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    pplane: dict[int, PPlane],
+    tolerant: bool = False,
+) -> None: ...
+def verify_maximal(
+    f: dict[int, set[int]],
+    layer: list[int],
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    pplane: dict[int, PPlane],
+) -> None: ...
+def check_embedding(
+    gflow: dict[int, set[int]],
+    pflow: dict[int, set[int]],
+    g: list[set[int]],
+    iset: set[int],
+    oset: set[int],
+    plane: dict[int, Plane],
+    pplane: dict[int, PPlane],
+) -> None: ...
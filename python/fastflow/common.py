@@ -4,10 +4,13 @@ from __future__ import annotations
 
 import dataclasses
 from collections.abc import Hashable
-from typing import Generic, TypeVar
+from typing import TYPE_CHECKING, Generic, TypeVar
 
 from fastflow._impl import gflow, pflow
 
+if TYPE_CHECKING:
+    from fastflow._impl.common import FindStats
+
 Plane = gflow.Plane
 PPlane = pflow.PPlane
 
@@ -46,3 +49,38 @@ class GFlowResult(Generic[_V]):
 
     f: dict[_V, set[_V]]
     layer: dict[_V, int]
+
+
+@dataclasses.dataclass(frozen=True)
+class GFlowReport(Generic[_V]):
+    """Generalized flow bundled with the data derived from it.
+
+    Attributes
+    ----------
+    f : `dict[V, set[V]]`
+        Gflow function.
+    layer : `dict[V, int]`
+        Layer of each vertex representing the partial order.
+        (u -> v iff `layer[u] > layer[v]`).
+    depth : `int`
+        Number of layers.
+    odd : `dict[V, set[V]]`
+        Odd neighbors of `f[u]` for each `u` in `f`.
+    order : `list[tuple[V, V]]`
+        Ordering constraints `(u, v)`, meaning that `u` must be measured before `v`.
+    rank : `dict[V, int]`
+        Rank of the linear system that determined `f[u]` for each `u` in `f`.
+    nullity : `dict[V, int]`
+        Number of free variables in the same linear system, zero if `f[u]` is unique.
+    stats : `FindStats`
+        Solver statistics.
+    """
+
+    f: dict[_V, set[_V]]
+    layer: dict[_V, int]
+    depth: int
+    odd: dict[_V, set[_V]]
+    order: list[tuple[_V, _V]]
+    rank: dict[_V, int]
+    nullity: dict[_V, int]
+    stats: FindStats
@@ -15,6 +15,7 @@ from fastflow._impl import flow
 from fastflow.common import FlowResult
 
 if TYPE_CHECKING:
+    from collections.abc import Mapping
     from collections.abc import Set as AbstractSet
 
     import networkx as nx
@@ -52,3 +53,215 @@ def find(g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]) -> FlowResu
         layer = codec.decode_layer(layer_)
         return FlowResult(f, layer)
     return None
+
+
+def exists(g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]) -> bool:
+    """Check if a causal flow exists, without constructing it.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+
+    Returns
+    -------
+    `True` if and only if `find` returns a flow.
+    """
+    _common.check_graph(g, iset, oset)
+    codec = IndexMap(g.nodes)
+    return flow.exists(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset))
+
+
+def dag(g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]) -> list[tuple[V, V]] | None:
+    """Compute the partial order induced by the maximally-delayed causal flow, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+
+    Returns
+    -------
+    If a flow exists, return edges `(i, j)` meaning that `i` must be measured before `j`.
+    Otherwise, return `None`.
+    """
+    _common.check_graph(g, iset, oset)
+    codec = IndexMap(g.nodes)
+    edges_ = flow.dag(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset))
+    if edges_ is None:
+        return None
+    return [(codec.decode(i), codec.decode(j)) for i, j in edges_]
+
+
+def depth(g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]) -> int | None:
+    """Compute the number of layers of the maximally-delayed causal flow, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+
+    Returns
+    -------
+    If a flow exists, return `max(layer) + 1`.
+    Otherwise, return `None`.
+    """
+    _common.check_graph(g, iset, oset)
+    codec = IndexMap(g.nodes)
+    return flow.depth(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset))
+
+
+def layers(g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]) -> list[set[V]] | None:
+    """Compute the maximally-delayed causal flow and group the nodes by layer, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+
+    Returns
+    -------
+    If a flow exists, return the set of nodes in each layer, indexed by layer number.
+    Otherwise, return `None`.
+    """
+    _common.check_graph(g, iset, oset)
+    codec = IndexMap(g.nodes)
+    layers_ = flow.layers(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset))
+    if layers_ is None:
+        return None
+    return [codec.decode_set(nodes) for nodes in layers_]
+
+
+def find_or_blocker(
+    g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]
+) -> tuple[FlowResult[V] | None, V | None]:
+    """Compute the maximally-delayed causal flow, or the node blocking it.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+
+    Returns
+    -------
+    `(result, None)` if a flow exists, with `result` being a `FlowResult[V]` object as in `find`.
+    Otherwise, `(None, blocker)` with `blocker` being the candidate corrector with the fewest (at least two) \
+uncorrected neighbors, or the first uncorrected node if no candidate has any uncorrected neighbor.
+    """
+    _common.check_graph(g, iset, oset)
+    codec = IndexMap(g.nodes)
+    found_, blocker_ = flow.find_or_blocker(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset))
+    if found_ is None:
+        return None, None if blocker_ is None else codec.decode(blocker_)
+    f_, layer_ = found_
+    return FlowResult(codec.decode_flow(f_), codec.decode_layer(layer_)), None
+
+
+def find_bounded(g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V], max_depth: int) -> FlowResult[V] | None:
+    """Compute the maximally-delayed causal flow with at most `max_depth` layers, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    max_depth : `int`
+        Maximum number of layers, outputs included.
+        Must be non-negative.
+
+    Returns
+    -------
+    If a flow with at most `max_depth` layers exists, return a `FlowResult[V]` object as in `find`.
+    Otherwise, return `None`.
+    """
+    _common.check_graph(g, iset, oset)
+    if max_depth < 0:
+        msg = "max_depth must be non-negative."
+        raise ValueError(msg)
+    codec = IndexMap(g.nodes)
+    ret_ = flow.find_bounded(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset), max_depth)
+    if ret_ is None:
+        return None
+    f_, layer_ = ret_
+    return FlowResult(codec.decode_flow(f_), codec.decode_layer(layer_))
+
+
+def verify_all(
+    f: Mapping[V, V], layer: Mapping[V, int], g: nx.Graph[V], iset: AbstractSet[V], oset: AbstractSet[V]
+) -> list[str]:
+    """Verify the causal flow, collecting all the violations.
+
+    Parameters
+    ----------
+    f : `Mapping[V, V]`
+        Flow function.
+    layer : `Mapping[V, int]`
+        Layer of each vertex.
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+
+    Returns
+    -------
+    The message of each violation, empty if `(f, layer)` is a valid flow.
+
+    Notes
+    -----
+    Messages refer to the vertices by their position in `g.nodes`.
+    """
+    _common.check_graph(g, iset, oset)
+    codec = IndexMap(g.nodes)
+    return flow.verify_all(
+        codec.encode_flow(f),
+        codec.encode_layer(layer),
+        codec.encode_graph(g),
+        codec.encode_set(iset),
+        codec.encode_set(oset),
+    )
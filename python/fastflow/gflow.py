@@ -13,14 +13,35 @@ from typing import TYPE_CHECKING, Mapping
 from fastflow import _common
 from fastflow._common import IndexMap, V
 from fastflow._impl import gflow
-from fastflow.common import GFlowResult, Plane
+from fastflow.common import GFlowReport, GFlowResult, Plane
 
 if TYPE_CHECKING:
+    from array import array
+    from collections.abc import Callable, Sequence
     from collections.abc import Set as AbstractSet
 
     import networkx as nx
 
 
+def _encode_plane(
+    codec: IndexMap[V], vset: AbstractSet[V], oset: AbstractSet[V], plane: Mapping[V, Plane] | None
+) -> dict[int, Plane]:
+    """Check and encode `plane`, defaulting to all `Plane.XY`.
+
+    Returns
+    -------
+    Encoded `plane`.
+    """
+    if plane is None:
+        plane = dict.fromkeys(vset - oset, Plane.XY)
+    _common.check_planelike(vset, oset, plane)
+    plane_ = codec.encode_dictkey(plane)
+    if len(plane_) != len(plane):
+        msg = "Ignoring plane[v] where v in oset."
+        warnings.warn(msg, stacklevel=1)
+    return plane_
+
+
 def find(
     g: nx.Graph[V],
     iset: AbstractSet[V],
@@ -51,20 +72,373 @@ def find(
     """
     _common.check_graph(g, iset, oset)
     vset = g.nodes
-    if plane is None:
-        plane = dict.fromkeys(vset - oset, Plane.XY)
-    _common.check_planelike(vset, oset, plane)
     codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
     g_ = codec.encode_graph(g)
     iset_ = codec.encode_set(iset)
     oset_ = codec.encode_set(oset)
-    plane_ = codec.encode_dictkey(plane)
-    if len(plane_) != len(plane):
-        msg = "Ignoring plane[v] where v in oset."
-        warnings.warn(msg, stacklevel=1)
     if ret_ := gflow.find(g_, iset_, oset_, plane_):
         f_, layer_ = ret_
         f = codec.decode_gflow(f_)
         layer = codec.decode_layer(layer_)
         return GFlowResult(f, layer)
     return None
+
+
+def exists(
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    plane: Mapping[V, Plane] | None = None,
+) -> bool:
+    r"""Check if a generalized flow exists, without constructing it.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    plane : `Mapping[V, Plane] | None`, optional
+        Measurement planes of each vertex in V\O.
+        If `None`, defaults to all `Plane.XY`.
+
+    Returns
+    -------
+    `True` if and only if `find` returns a gflow.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
+    return gflow.exists(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset), plane_)
+
+
+def find_min(
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    plane: Mapping[V, Plane] | None = None,
+) -> GFlowResult[V] | None:
+    r"""Compute the maximally-delayed generalized flow with the smallest correction sets, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    plane : `Mapping[V, Plane] | None`, optional
+        Measurement planes of each vertex in V\O.
+        If `None`, defaults to all `Plane.XY`.
+
+    Returns
+    -------
+    If a gflow exists, return a `GFlowResult[V]` object with the same layer as `find`.
+    Otherwise, return `None`.
+
+    Notes
+    -----
+    Nodes with too many candidate corrections keep the one chosen by `find`.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
+    if ret_ := gflow.find_min(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset), plane_):
+        f_, layer_ = ret_
+        f = codec.decode_gflow(f_)
+        layer = codec.decode_layer(layer_)
+        return GFlowResult(f, layer)
+    return None
+
+
+def find_verbose(
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    plane: Mapping[V, Plane] | None = None,
+) -> GFlowReport[V] | None:
+    r"""Compute the maximally-delayed generalized flow along with the data derived from it, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    plane : `Mapping[V, Plane] | None`, optional
+        Measurement planes of each vertex in V\O.
+        If `None`, defaults to all `Plane.XY`.
+
+    Returns
+    -------
+    If a gflow exists, return a `GFlowReport[V]` object with the same gflow and layer as `find`.
+    Otherwise, return `None`.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
+    report = gflow.find_verbose(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset), plane_)
+    if report is None:
+        return None
+    return GFlowReport(
+        codec.decode_gflow(report.f),
+        codec.decode_layer(report.layer),
+        report.depth,
+        codec.decode_gflow(report.odd),
+        [(codec.decode(i), codec.decode(j)) for i, j in report.order],
+        {codec.decode(i): r for i, r in report.rank.items()},
+        {codec.decode(i): k for i, k in report.nullity.items()},
+        report.stats,
+    )
+
+
+def find_all(
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    limit: int,
+    plane: Mapping[V, Plane] | None = None,
+) -> list[GFlowResult[V]]:
+    r"""Enumerate the maximally-delayed generalized flows.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    limit : `int`
+        Maximum number of gflows to return.
+    plane : `Mapping[V, Plane] | None`, optional
+        Measurement planes of each vertex in V\O.
+        If `None`, defaults to all `Plane.XY`.
+
+    Returns
+    -------
+    Distinct `GFlowResult[V]` objects sharing the same layer, the first one being the output of `find`.
+    Empty if no gflow exists.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
+    ret_ = gflow.find_all(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset), plane_, limit)
+    return [GFlowResult(codec.decode_gflow(f_), codec.decode_layer(layer_)) for f_, layer_ in ret_]
+
+
+def find_multiplane(
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    allowed: Mapping[V, Sequence[Plane]],
+) -> tuple[GFlowResult[V], dict[V, Plane]] | None:
+    r"""Compute the maximally-delayed generalized flow, choosing the measurement plane of each vertex.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    allowed : `Mapping[V, Sequence[Plane]]`
+        Allowed measurement planes of each vertex in V\O, in the order of precedence.
+
+    Returns
+    -------
+    If a gflow exists, return a `GFlowResult[V]` object and the chosen plane of each vertex in V\O.
+    Otherwise, return `None`.
+
+    Notes
+    -----
+    If several planes work in the same layer, the first one in `allowed[v]` is chosen.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    _common.check_planelike(vset, oset, allowed)
+    codec = IndexMap(vset)
+    allowed_ = {codec.encode(v): list(planes) for v, planes in allowed.items()}
+    ret_ = gflow.find_multiplane(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset), allowed_)
+    if ret_ is None:
+        return None
+    f_, layer_, plane_ = ret_
+    result = GFlowResult(codec.decode_gflow(f_), codec.decode_layer(layer_))
+    return result, {codec.decode(i): pi for i, pi in plane_.items()}
+
+
+def find_with_forbidden_odd(
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    forbidden: AbstractSet[V],
+    plane: Mapping[V, Plane] | None = None,
+) -> GFlowResult[V] | None:
+    r"""Compute the maximally-delayed generalized flow with some vertices excluded from the odd neighbors, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    forbidden : `AbstractSet[V]`
+        Vertices never allowed in the odd neighbors of `f[u]` for any `u` other than themselves.
+        Must be a subset of `g.nodes`.
+    plane : `Mapping[V, Plane] | None`, optional
+        Measurement planes of each vertex in V\O.
+        If `None`, defaults to all `Plane.XY`.
+
+    Returns
+    -------
+    If a gflow exists, return a `GFlowResult[V]` object.
+    Otherwise, return `None`.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
+    g_ = codec.encode_graph(g)
+    iset_ = codec.encode_set(iset)
+    oset_ = codec.encode_set(oset)
+    if ret_ := gflow.find_with_forbidden_odd(g_, iset_, oset_, plane_, codec.encode_set(forbidden)):
+        f_, layer_ = ret_
+        return GFlowResult(codec.decode_gflow(f_), codec.decode_layer(layer_))
+    return None
+
+
+def find_csr(
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    plane: Mapping[V, Plane] | None = None,
+) -> tuple[array[int], array[int], dict[V, int]] | None:
+    r"""Compute the maximally-delayed generalized flow as a CSR-like pair of integer arrays, if any.
+
+    Parameters
+    ----------
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    plane : `Mapping[V, Plane] | None`, optional
+        Measurement planes of each vertex in V\O.
+        If `None`, defaults to all `Plane.XY`.
+
+    Returns
+    -------
+    If a gflow exists, return `(indptr, indices, layer)`.
+    Otherwise, return `None`.
+
+    Notes
+    -----
+    Vertices are numbered in the order of `g.nodes`.
+    With `nodes = list(g.nodes)`, `f[nodes[i]]` is `{nodes[j] for j in indices[indptr[i] : indptr[i + 1]]}`.
+    Both arrays are `array.array` of typecode `"Q"`, shared by `numpy.asarray` without copying.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
+    ret_ = gflow.find_csr(codec.encode_graph(g), codec.encode_set(iset), codec.encode_set(oset), plane_)
+    if ret_ is None:
+        return None
+    indptr, indices, layer_ = ret_
+    return indptr, indices, codec.decode_layer(layer_)
+
+
+def verify(  # noqa: PLR0913
+    f: Mapping[V, AbstractSet[V]],
+    layer: Mapping[V, int],
+    g: nx.Graph[V],
+    iset: AbstractSet[V],
+    oset: AbstractSet[V],
+    plane: Mapping[V, Plane] | None = None,
+    tolerant: bool = False,
+    progress: Callable[[int, int], object] | None = None,
+) -> None:
+    r"""Verify the generalized flow.
+
+    Parameters
+    ----------
+    f : `Mapping[V, AbstractSet[V]]`
+        Gflow function.
+    layer : `Mapping[V, int]`
+        Layer of each vertex.
+    g : `nx.Graph[V]`
+        Undirected graph representing MBQC pattern.
+        Cannot have self-loops.
+    iset : `AbstractSet[V]`
+        Input nodes.
+        Must be a subset of `g.nodes`.
+    oset : `AbstractSet[V]`
+        Output nodes.
+        Must be a subset of `g.nodes`.
+    plane : `Mapping[V, Plane] | None`, optional
+        Measurement planes of each vertex in V\O.
+        If `None`, defaults to all `Plane.XY`.
+    tolerant : `bool`, optional
+        Ignore the entries of `plane` for the output nodes instead of failing.
+    progress : `Callable[[int, int], object] | None`, optional
+        Called as `progress(nodes_checked, total)` after each vertex in `f` passes.
+
+    Raises
+    ------
+    ValueError
+        If `(f, layer)` is not a valid gflow.
+
+    Notes
+    -----
+    Error messages refer to the vertices by their position in `g.nodes`.
+    Exceptions raised by `progress` are propagated as is.
+    """
+    _common.check_graph(g, iset, oset)
+    vset = g.nodes
+    codec = IndexMap(vset)
+    plane_ = _encode_plane(codec, vset, oset, plane)
+    gflow.verify(
+        codec.encode_gflow(f),
+        codec.encode_layer(layer),
+        codec.encode_graph(g),
+        codec.encode_set(iset),
+        codec.encode_set(oset),
+        plane_,
+        tolerant,
+        progress,
+    )
@@ -1,6 +1,20 @@
 //! Common functionalities.
+#![allow(clippy::useless_conversion)]
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use pyo3::prelude::*;
+
+use crate::{
+    gflow::Plane,
+    internal::{
+        gf2_linalg::{self, GF2Solver, SolverState},
+        utils, validate,
+    },
+    pflow::PPlane,
+};
 
 /// Set of nodes indexed by 0-based integers.
 pub type Nodes = hashbrown::HashSet<usize>;
@@ -15,3 +29,1237 @@ pub type Layer = Vec<usize>;
 ///
 /// Used only when iteration order matters.
 pub(crate) type OrderedNodes = BTreeSet<usize>;
+
+#[pyclass(frozen, get_all)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Statistics collected while finding flows.
+pub struct FindStats {
+    /// Maximum number of ones in a row of the coefficient matrices after elimination.
+    pub max_row_weight: usize,
+    /// Number of ones in the coefficient matrices after elimination, summed over all the solves.
+    pub total_ones: usize,
+    /// Number of pivot swaps, summed over all the solves.
+    pub pivot_swaps: usize,
+    /// Number of row XORs, summed over all the solves.
+    pub row_xors: usize,
+    /// Number of times each Pauli flow branch (`XY`, `YZ`, or `XZ`) was tried.
+    pub branch_attempts: hashbrown::HashMap<PPlane, usize>,
+    /// Number of times each Pauli flow branch found a solution.
+    pub branch_successes: hashbrown::HashMap<PPlane, usize>,
+}
+
+impl FindStats {
+    /// Accumulates the statistics of an eliminated `solver`.
+    pub(crate) fn record_solver(&mut self, solver: &GF2Solver) {
+        self.max_row_weight = self.max_row_weight.max(solver.max_row_weight());
+        self.total_ones += solver.total_ones();
+        let (swaps, xors) = solver.op_counts();
+        self.pivot_swaps += swaps;
+        self.row_xors += xors;
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+/// Linear system over GF(2) with a fixed coefficient matrix and growing right-hand sides.
+///
+/// The elimination is done at most once and reused by all the right-hand sides.
+pub struct GF2System {
+    /// Coefficient matrix followed by the right-hand sides, eliminated in place.
+    work: Vec<FixedBitSet>,
+    /// Number of columns of the coefficient matrix.
+    cols: usize,
+    /// Number of right-hand sides.
+    neqs: usize,
+    /// Solver state, available after the first `solve`.
+    state: Option<SolverState>,
+}
+
+impl GF2System {
+    /// Resumes the solver, or attaches a new one recording the row operations for `add_rhs`.
+    fn solver(&mut self) -> GF2Solver<'_> {
+        if let Some(state) = self.state.take() {
+            return GF2Solver::resume(&mut self.work, state);
+        }
+        let mut solver = GF2Solver::attach(&mut self.work, self.neqs);
+        solver.record_ops();
+        solver
+    }
+
+    /// Creates a system from the rows of the coefficient matrix, each of length `cols`.
+    fn from_rows(work: Vec<FixedBitSet>, cols: usize) -> Self {
+        debug_assert!(work.iter().all(|row| row.len() == cols));
+        Self {
+            work,
+            cols,
+            neqs: 0,
+            state: None,
+        }
+    }
+}
+
+#[pymethods]
+impl GF2System {
+    /// Creates a system with the coefficient matrix `a`.
+    ///
+    /// # Errors
+    ///
+    /// If `a` is empty, jagged, or has no columns.
+    #[new]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn new(a: Vec<Vec<bool>>) -> PyResult<Self> {
+        let check = || {
+            let Ok(cols) = a.iter().map(Vec::len).all_equal_value() else {
+                anyhow::bail!("a must be a non-empty rectangular matrix");
+            };
+            anyhow::ensure!(cols > 0, "a must have at least one column");
+            Ok(cols)
+        };
+        let cols = check().map_err(utils::to_pyerr)?;
+        let work = a
+            .iter()
+            .map(|row| {
+                let mut bits = FixedBitSet::with_capacity(cols);
+                bits.extend(row.iter().positions(|&b| b));
+                bits
+            })
+            .collect();
+        Ok(Self::from_rows(work, cols))
+    }
+
+    /// Appends the right-hand side `b`.
+    ///
+    /// # Returns
+    ///
+    /// Index of the new equation, to be passed to `solve`.
+    ///
+    /// # Errors
+    ///
+    /// If `b` has a different length from the number of rows.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn add_rhs(&mut self, b: Vec<bool>) -> PyResult<usize> {
+        if b.len() != self.work.len() {
+            let err = anyhow::anyhow!("b size mismatch: {} != {}", b.len(), self.work.len());
+            return Err(utils::to_pyerr(err));
+        }
+        let mut rhs = FixedBitSet::with_capacity(b.len());
+        rhs.extend(b.iter().positions(|&x| x));
+        let ieq = if let Some(state) = self.state.take() {
+            // Replay the elimination on b only
+            let mut solver = GF2Solver::resume(&mut self.work, state);
+            let ieq = solver.push_rhs(&rhs);
+            self.state = Some(solver.suspend());
+            ieq
+        } else {
+            let c = self.cols + self.neqs;
+            for (row, x) in self.work.iter_mut().zip(b) {
+                row.grow(c + 1);
+                row.set(c, x);
+            }
+            self.neqs
+        };
+        self.neqs += 1;
+        Ok(ieq)
+    }
+
+    /// Solves the equation indexed by `ieq`.
+    ///
+    /// # Returns
+    ///
+    /// One of the solutions, `None` if not solvable.
+    ///
+    /// # Errors
+    ///
+    /// If `ieq` is out of range.
+    pub fn solve(&mut self, ieq: usize) -> PyResult<Option<Vec<bool>>> {
+        if ieq >= self.neqs {
+            let err = anyhow::anyhow!("equation index out of range: {ieq} >= {}", self.neqs);
+            return Err(utils::to_pyerr(err));
+        }
+        let cols = self.cols;
+        let mut solver = self.solver();
+        let mut x = FixedBitSet::with_capacity(cols);
+        let ok = solver.solve_in_place(&mut x, ieq);
+        self.state = Some(solver.suspend());
+        Ok(ok.then(|| (0..cols).map(|i| x[i]).collect()))
+    }
+
+    /// Computes the rank of the coefficient matrix.
+    ///
+    /// The elimination is cached as in `solve` if any right-hand side is present.
+    pub fn rank(&mut self) -> usize {
+        if self.neqs == 0 {
+            return gf2_linalg::rank_fast(&mut self.work.clone(), self.cols);
+        }
+        let mut solver = self.solver();
+        let rank = solver.rank();
+        self.state = Some(solver.suspend());
+        rank
+    }
+
+    /// Number of right-hand sides added so far.
+    #[getter]
+    #[must_use]
+    pub fn neqs(&self) -> usize {
+        self.neqs
+    }
+}
+
+/// Solves `a x = b` for each `b` in `bs`, reporting the rank of `a` as well.
+///
+/// # Returns
+///
+/// One of the solutions or `None`, paired with the rank of `a`, for each `b`.
+///
+/// # Errors
+///
+/// If `a` or `bs` is malformed, as in `GF2System`.
+///
+/// # Note
+///
+/// - `a` is eliminated only once, so the rank is shared by all the entries.
+/// - An unsolvable `b` gives `None` regardless of the rank, while a solvable one has `2^(ncols - rank)` solutions.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn solve_with_rank(
+    a: Vec<Vec<bool>>,
+    bs: Vec<Vec<bool>>,
+) -> PyResult<Vec<(Option<Vec<bool>>, usize)>> {
+    let mut sys = GF2System::new(a)?;
+    for b in bs {
+        sys.add_rhs(b)?;
+    }
+    let rank = sys.rank();
+    (0..sys.neqs)
+        .map(|ieq| Ok((sys.solve(ieq)?, rank)))
+        .collect()
+}
+
+/// Converts a 0/1 integer matrix into a boolean one.
+fn int_to_bool(name: &str, m: &[Vec<i64>]) -> anyhow::Result<Vec<Vec<bool>>> {
+    m.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&x| match x {
+                    0 => Ok(false),
+                    1 => Ok(true),
+                    _ => anyhow::bail!("{name} must consist of 0 and 1: got {x}"),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Same as `solve_with_rank`, but with `a` and `bs` given as 0/1 integers and without the rank.
+///
+/// # Errors
+///
+/// If any entry is neither 0 nor 1, or `a` or `bs` is malformed.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn solve_int(a: Vec<Vec<i64>>, bs: Vec<Vec<i64>>) -> PyResult<Vec<Option<Vec<bool>>>> {
+    let a = int_to_bool("a", &a).map_err(utils::to_pyerr)?;
+    let bs = int_to_bool("bs", &bs).map_err(utils::to_pyerr)?;
+    let mut sys = GF2System::new(a)?;
+    for b in bs {
+        sys.add_rhs(b)?;
+    }
+    (0..sys.neqs).map(|ieq| sys.solve(ieq)).collect()
+}
+
+/// Same as `solve_with_rank`, but with `a` given in the CSR format without the rank.
+///
+/// # Arguments
+///
+/// - `indptr`: Row pointers, as `scipy.sparse.csr_array.indptr`.
+/// - `indices`: Column indices, as `scipy.sparse.csr_array.indices`.
+/// - `shape`: Number of rows and columns.
+/// - `bs`: Right-hand sides.
+///
+/// # Errors
+///
+/// If the CSR arrays are inconsistent with `shape`, or `bs` is malformed.
+///
+/// # Note
+///
+/// - Duplicate entries are summed modulo 2, as `scipy` does when converting to dense.
+/// - No dense intermediate is created.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn solve_sparse(
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    shape: (usize, usize),
+    bs: Vec<Vec<bool>>,
+) -> PyResult<Vec<Option<Vec<bool>>>> {
+    let (rows, cols) = shape;
+    let check = || {
+        anyhow::ensure!(rows > 0 && cols > 0, "shape must be positive: {shape:?}");
+        anyhow::ensure!(
+            indptr.len() == rows + 1,
+            "indptr size mismatch: {} != {}",
+            indptr.len(),
+            rows + 1
+        );
+        anyhow::ensure!(
+            indptr[0] == 0 && indptr.windows(2).all(|w| w[0] <= w[1]),
+            "indptr must be non-decreasing from 0"
+        );
+        anyhow::ensure!(
+            indptr[rows] == indices.len(),
+            "indptr and indices size mismatch: {} != {}",
+            indptr[rows],
+            indices.len()
+        );
+        if let Some(&c) = indices.iter().find(|&&c| c >= cols) {
+            anyhow::bail!("column index out of range: {c} >= {cols}");
+        }
+        Ok(())
+    };
+    check().map_err(utils::to_pyerr)?;
+    let work = indptr
+        .windows(2)
+        .map(|w| {
+            let mut row = FixedBitSet::with_capacity(cols);
+            for &c in &indices[w[0]..w[1]] {
+                row.toggle(c);
+            }
+            row
+        })
+        .collect();
+    let mut sys = GF2System::from_rows(work, cols);
+    for b in bs {
+        sys.add_rhs(b)?;
+    }
+    (0..sys.neqs).map(|ieq| sys.solve(ieq)).collect()
+}
+
+/// Packs the rows of `a` after checking that it is square.
+fn square_rows(a: &[Vec<bool>]) -> anyhow::Result<Vec<FixedBitSet>> {
+    let n = a.len();
+    if let Some(row) = a.iter().find(|row| row.len() != n) {
+        anyhow::bail!("a must be square: {n} rows but {} columns", row.len());
+    }
+    Ok(a.iter()
+        .map(|row| {
+            let mut bits = FixedBitSet::with_capacity(n);
+            bits.extend(row.iter().positions(|&b| b));
+            bits
+        })
+        .collect())
+}
+
+/// Computes the determinant of the square matrix `a` over GF(2).
+///
+/// # Returns
+///
+/// `true` if `a` is nonsingular.
+///
+/// # Errors
+///
+/// If `a` is not square.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn det_gf2(a: Vec<Vec<bool>>) -> PyResult<bool> {
+    let n = a.len();
+    let mut work = square_rows(&a).map_err(utils::to_pyerr)?;
+    Ok(gf2_linalg::rank_fast(&mut work, n) == n)
+}
+
+/// Inverts the square matrix `a` over GF(2).
+///
+/// # Returns
+///
+/// The inverse, `None` if singular.
+///
+/// # Errors
+///
+/// If `a` is not square.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn invert(a: Vec<Vec<bool>>) -> PyResult<Option<Vec<Vec<bool>>>> {
+    let n = a.len();
+    let mut work = square_rows(&a).map_err(utils::to_pyerr)?;
+    let Some(inv) = gf2_linalg::invert(&mut work) else {
+        return Ok(None);
+    };
+    Ok(Some(
+        inv.iter()
+            .map(|row| (0..n).map(|c| row[c]).collect())
+            .collect(),
+    ))
+}
+
+/// Computes the dimension of the kernel of `a`, i.e., `ncols - rank`.
+///
+/// # Errors
+///
+/// If `a` is malformed, as in `GF2System`.
+#[pyfunction]
+pub fn nullity(a: Vec<Vec<bool>>) -> PyResult<usize> {
+    let mut sys = GF2System::new(a)?;
+    Ok(sys.cols - sys.rank())
+}
+
+/// Solves `a x = b` for a single right-hand side `b`.
+///
+/// # Returns
+///
+/// One of the solutions, `None` if not solvable.
+///
+/// # Errors
+///
+/// If `a` or `b` is malformed, as in `GF2System`.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn solve_one(a: Vec<Vec<bool>>, b: Vec<bool>) -> PyResult<Option<Vec<bool>>> {
+    let mut sys = GF2System::new(a)?;
+    let ieq = sys.add_rhs(b)?;
+    sys.solve(ieq)
+}
+
+/// Enumerates all the solutions of `a x = b`.
+///
+/// # Arguments
+///
+/// - `a`: Coefficient matrix.
+/// - `b`: Right-hand side.
+/// - `max_free`: Maximum number of free variables allowed.
+///
+/// # Returns
+///
+/// Every solution, in no particular order. Empty if not solvable.
+///
+/// # Errors
+///
+/// - If `a` or `b` is malformed, as in `GF2System`.
+/// - If `a` has more than `max_free` free variables, i.e., more than `2^max_free` solutions.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn solve_all_solutions(
+    a: Vec<Vec<bool>>,
+    b: Vec<bool>,
+    max_free: usize,
+) -> PyResult<Vec<Vec<bool>>> {
+    let mut sys = GF2System::new(a)?;
+    let ieq = sys.add_rhs(b)?;
+    let cols = sys.cols;
+    let mut solver = GF2Solver::attach(&mut sys.work, sys.neqs);
+    let nfree = solver.free_variables().len();
+    if nfree > max_free {
+        let err = anyhow::anyhow!("too many free variables: {nfree} > {max_free}");
+        return Err(utils::to_pyerr(err));
+    }
+    let Some(xs) = solver.solutions(ieq) else {
+        return Ok(vec![]);
+    };
+    Ok(xs.map(|x| (0..cols).map(|i| x[i]).collect()).collect())
+}
+
+/// Finds an edge missing its reverse direction.
+///
+/// # Returns
+///
+/// The first `(u, v)` in ascending order such that `v` is in `g[u]` but `u` is not in `g[v]`.
+/// `v` out of range is also reported.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_asymmetry(g: Graph) -> Option<(usize, usize)> {
+    g.iter().enumerate().find_map(|(u, gu)| {
+        gu.iter()
+            .copied()
+            .filter(|&v| g.get(v).is_none_or(|gv| !gv.contains(&u)))
+            .min()
+            .map(|v| (u, v))
+    })
+}
+
+/// Checks if the graph is undirected.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn is_symmetric(g: Graph) -> bool {
+    find_asymmetry(g).is_none()
+}
+
+/// Checks if the node references fit in the graph.
+///
+/// # Arguments
+///
+/// - `g`: The adjacency list of the graph.
+/// - `iset`: The set of initial nodes.
+/// - `oset`: The set of output nodes.
+/// - `meas`: Measurement plane of each node, either `Plane` or `PPlane`.
+///
+/// # Errors
+///
+/// If any node is not in `0..g.len()`, or some node is neither in `meas` nor in `oset`.
+///
+/// # Note
+///
+/// - Only the node indices are checked, not the structure of `g`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn check_node_count(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    meas: hashbrown::HashMap<usize, AnyPlane>,
+) -> PyResult<()> {
+    let meas = meas.keys().copied().collect::<Nodes>();
+    validate::check_node_count(g.len(), &iset, &oset, &meas).map_err(utils::to_pyerr)
+}
+
+/// Builds an undirected graph from edges.
+///
+/// # Arguments
+///
+/// - `n`: Number of nodes.
+/// - `edges`: Undirected edges `(u, v)`, each listed once unless `symmetric`.
+/// - `symmetric`: If `true`, `edges` lists each edge in both directions, as `(u, v)` and `(v, u)`.
+///
+/// # Errors
+///
+/// If `edges` contains self-loops, duplicates, or nodes other than `0..n`.
+/// If `symmetric`, also if some edge is not listed the same number of times in each direction.
+#[pyfunction]
+#[pyo3(signature = (n, edges, symmetric = false))]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn graph_from_edges(n: usize, edges: Vec<(usize, usize)>, symmetric: bool) -> PyResult<Graph> {
+    if symmetric {
+        validate::check_arcs(n, &edges)
+    } else {
+        validate::check_edges(n, &edges)
+    }
+    .map_err(utils::to_pyerr)?;
+    let mut g = vec![Nodes::new(); n];
+    for (u, v) in edges {
+        g[u].insert(v);
+        g[v].insert(u);
+    }
+    Ok(g)
+}
+
+/// Lists the edges of an undirected graph.
+///
+/// # Returns
+///
+/// Edges `(u, v)` with `u < v`, sorted in ascending order.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn to_edges(g: Graph) -> Vec<(usize, usize)> {
+    let mut edges = g
+        .iter()
+        .enumerate()
+        .flat_map(|(u, gu)| gu.iter().filter(move |&&v| u < v).map(move |&v| (u, v)))
+        .collect::<Vec<_>>();
+    edges.sort_unstable();
+    edges
+}
+
+/// Computes the nodes that must be measured before `node`.
+///
+/// # Arguments
+///
+/// - `node`: Node of interest.
+/// - `f`: Flow, gflow, or pflow, with `f[i]` being the correction set of `i`.
+/// - `layer`: Layer of each node.
+/// - `g`: The adjacency list of the graph.
+///
+/// # Returns
+///
+/// All the `i` from which `node` is reachable via the ordering constraints, `i -> j` for `j` in `f(i)` or `Odd(f(i))`.
+///
+/// # Panics
+///
+/// If `node` is out of range.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn past(node: usize, f: hashbrown::HashMap<usize, Nodes>, layer: Layer, g: Graph) -> Nodes {
+    let mut pred = vec![Vec::new(); g.len()];
+    for (i, j) in utils::order_edges(&f, &layer, &g) {
+        pred[j].push(i);
+    }
+    let mut ret = Nodes::new();
+    let mut stack = pred[node].clone();
+    while let Some(i) = stack.pop() {
+        if ret.insert(i) {
+            stack.extend(&pred[i]);
+        }
+    }
+    ret
+}
+
+/// Measurement specification of either `gflow` or `pflow`.
+#[derive(FromPyObject, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnyPlane {
+    Plane(Plane),
+    PPlane(PPlane),
+}
+
+/// Serializes the problem instance into a single-line JSON for bug reports.
+///
+/// # Returns
+///
+/// JSON object with the following keys, all sorted in ascending order:
+///
+/// - `"n"`: Number of nodes.
+/// - `"edges"`: Undirected edges `[u, v]` with `u < v`, accepted by `graph_from_edges`.
+/// - `"iset"`/`"oset"`: Input/output nodes.
+/// - `"planes"`: Measurement of each node, e.g. `"Plane.XY"` or `"PPlane.X"`.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn dump_case(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: hashbrown::HashMap<usize, AnyPlane>,
+) -> String {
+    let sorted = |nodes: &Nodes| nodes.iter().copied().sorted().collect::<Vec<_>>();
+    let edges = to_edges(g.clone())
+        .into_iter()
+        .map(|(u, v)| format!("[{u}, {v}]"))
+        .join(", ");
+    let planes = planes
+        .into_iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(u, pu)| match pu {
+            AnyPlane::Plane(pu) => format!("\"{u}\": \"Plane.{pu:?}\""),
+            AnyPlane::PPlane(pu) => format!("\"{u}\": \"PPlane.{pu:?}\""),
+        })
+        .join(", ");
+    format!(
+        "{{\"n\": {}, \"edges\": [{edges}], \"iset\": {:?}, \"oset\": {:?}, \"planes\": {{{planes}}}}}",
+        g.len(),
+        sorted(&iset),
+        sorted(&oset),
+    )
+}
+
+/// Computes a lower bound of the number of layers of flows.
+///
+/// # Returns
+///
+/// `1 + max(dist(u, oset))` over the nodes `u` connected to `oset`, or `0` if `oset` is empty.
+///
+/// # Note
+///
+/// - Lower bound, not exact: computed by BFS without solving any system.
+/// - Valid for causal flow and gflow with all the nodes measured in the XY plane,
+///   as each `u` then has a neighbor in a strictly lower layer.
+/// - Not valid for other planes or Pauli flow, which may correct `u` with distant nodes.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn depth_lower_bound(g: Graph, oset: Nodes) -> usize {
+    let mut visited = vec![false; g.len()];
+    let mut queue = oset.iter().map(|&u| (u, 0)).collect::<VecDeque<_>>();
+    for &u in &oset {
+        visited[u] = true;
+    }
+    let mut max = None;
+    while let Some((u, du)) = queue.pop_front() {
+        max = max.max(Some(du));
+        for &v in &g[u] {
+            if !visited[v] {
+                visited[v] = true;
+                queue.push_back((v, du + 1));
+            }
+        }
+    }
+    max.map_or(0, |d| d + 1)
+}
+
+/// Packs `layer` into a contiguous integer array.
+///
+/// # Returns
+///
+/// `array.array` of typecode `"Q"`, which supports the buffer protocol.
+///
+/// # Errors
+///
+/// If the `array` module fails to construct the result.
+///
+/// # Note
+///
+/// - `numpy.asarray` on the result shares the buffer without copying.
+/// - The list form returned by `find` is unchanged.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn layer_array(py: Python<'_>, layer: Layer) -> PyResult<Bound<'_, PyAny>> {
+    utils::u64_array(py, &layer)
+}
+
+/// Nodes measured in a step and the `X` and `Z` correction links `(i, j)` triggered after them.
+pub type Step = (Vec<usize>, Vec<(usize, usize)>, Vec<(usize, usize)>);
+
+/// Groups the measurements and corrections into steps in execution order.
+///
+/// # Arguments
+///
+/// - `f`: Flow, gflow, or pflow, with `f[i]` being the correction set of `i`.
+/// - `layer`: Layer of each node.
+/// - `g`: The adjacency list of the graph.
+///
+/// # Returns
+///
+/// For each layer in descending order, the sorted nodes in the domain of `f` measured in that layer,
+/// the sorted `(i, j)` for `j` in `f[i]`, and the sorted `(i, j)` for `j` in `Odd(f[i])`.
+/// The latter two are the `X` and `Z` corrections triggered after measuring the nodes.
+///
+/// # Note
+///
+/// - Self-links `(i, i)` are kept so that the steps reproduce `f` and `Odd(f)` entirely.
+///
+/// # Panics
+///
+/// If `layer` does not cover the domain of `f`, or `f` contains nodes out of range.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn stepwise_plan(f: hashbrown::HashMap<usize, Nodes>, layer: Layer, g: Graph) -> Vec<Step> {
+    let odd = utils::odd_neighbors_map(&f, &g);
+    let mut steps = BTreeMap::<usize, Step>::new();
+    for (&i, fi) in &f {
+        let (nodes, xlinks, zlinks) = steps.entry(layer[i]).or_default();
+        nodes.push(i);
+        xlinks.extend(fi.iter().map(|&j| (i, j)));
+        zlinks.extend(odd[&i].iter().map(|&j| (i, j)));
+    }
+    steps
+        .into_values()
+        .rev()
+        .map(|(mut nodes, mut xlinks, mut zlinks)| {
+            nodes.sort_unstable();
+            xlinks.sort_unstable();
+            zlinks.sort_unstable();
+            (nodes, xlinks, zlinks)
+        })
+        .collect()
+}
+
+/// Computes `Odd(f(i))` for each `i` in the domain of `f`.
+///
+/// # Panics
+///
+/// If `f` contains nodes out of range.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn odd_neighbors_of_flow(
+    f: hashbrown::HashMap<usize, Nodes>,
+    g: Graph,
+) -> hashbrown::HashMap<usize, Nodes> {
+    utils::odd_neighbors_map(&f, &g)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+
+    use super::*;
+    use crate::internal::test_utils;
+
+    #[test]
+    fn test_gf2_system_reuse() {
+        // x0 + x1 = b0, x1 + x2 = b1, x0 + x2 = b2
+        let a = vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![true, false, true],
+        ];
+        let mut sys = GF2System::new(a.clone()).unwrap();
+        assert_eq!(sys.add_rhs(vec![true, false, true]).unwrap(), 0);
+        let x = sys.solve(0).unwrap().unwrap();
+        let lhs = |x: &[bool]| {
+            a.iter()
+                .map(|row| row.iter().zip(x).filter(|&(&r, &xi)| r && xi).count() % 2 == 1)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lhs(&x), [true, false, true]);
+        assert!(sys.state.is_some());
+        let op_counts = |sys: &GF2System| {
+            let mut work = sys.work.clone();
+            GF2Solver::resume(&mut work, sys.state.clone().unwrap()).op_counts()
+        };
+        let counts = op_counts(&sys);
+        // Added after elimination
+        assert_eq!(sys.add_rhs(vec![true, true, true]).unwrap(), 1);
+        assert_eq!(sys.add_rhs(vec![false, true, true]).unwrap(), 2);
+        assert_eq!(sys.solve(1).unwrap(), None);
+        let y = sys.solve(2).unwrap().unwrap();
+        assert_eq!(lhs(&y), [false, true, true]);
+        assert_eq!(sys.solve(0).unwrap().unwrap(), x);
+        // Not eliminated again
+        assert_eq!(op_counts(&sys), counts);
+        assert_eq!(sys.neqs(), 3);
+        assert!(sys.solve(3).is_err());
+        assert!(sys.add_rhs(vec![true]).is_err());
+    }
+
+    #[test]
+    fn test_solve_with_rank() {
+        let a = vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![true, false, true],
+        ];
+        let bs = vec![vec![true, false, true], vec![true, true, true]];
+        let res = solve_with_rank(a.clone(), bs).unwrap();
+        assert_eq!(res.len(), 2);
+        assert!(res[0].0.is_some());
+        assert_eq!(res[1].0, None);
+        assert!(res.iter().all(|&(_, rank)| rank == 2));
+        // Full rank
+        let eye = vec![vec![true, false], vec![false, true]];
+        let res = solve_with_rank(eye, vec![vec![false, true]]).unwrap();
+        assert_eq!(res, [(Some(vec![false, true]), 2)]);
+        // Rank without right-hand sides
+        assert_eq!(GF2System::new(a).unwrap().rank(), 2);
+    }
+
+    #[test]
+    fn test_solve_one() {
+        let a = vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![true, false, true],
+        ];
+        for b in [vec![true, false, true], vec![true, true, true]] {
+            let expected = solve_with_rank(a.clone(), vec![b.clone()]).unwrap();
+            assert_eq!(solve_one(a.clone(), b).unwrap(), expected[0].0);
+        }
+        assert!(solve_one(a, vec![true]).is_err());
+    }
+
+    #[test]
+    fn test_nullity() {
+        let a = vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![true, false, true],
+        ];
+        assert_eq!(nullity(a).unwrap(), 1);
+        let eye = vec![vec![true, false], vec![false, true]];
+        assert_eq!(nullity(eye).unwrap(), 0);
+        let zero = vec![vec![false; 4]; 2];
+        assert_eq!(nullity(zero).unwrap(), 4);
+        // Wide
+        let wide = vec![vec![true, false, true, true, false]];
+        assert_eq!(nullity(wide).unwrap(), 4);
+        assert!(nullity(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_solve_sparse() {
+        // 1 1 0 0
+        // 0 1 1 0
+        // 1 0 1 1
+        let a = vec![
+            vec![true, true, false, false],
+            vec![false, true, true, false],
+            vec![true, false, true, true],
+        ];
+        let indptr = vec![0, 2, 4, 7];
+        let indices = vec![0, 1, 1, 2, 0, 2, 3];
+        let bs = vec![
+            vec![true, false, true],
+            vec![true, true, true],
+            vec![false, false, false],
+        ];
+        let dense = solve_with_rank(a, bs.clone()).unwrap();
+        let sparse = solve_sparse(indptr.clone(), indices.clone(), (3, 4), bs.clone()).unwrap();
+        for ((x, _), y) in dense.into_iter().zip(sparse) {
+            assert_eq!(x, y);
+        }
+        // Duplicates cancel out: 1 + 1 = 0
+        let dup = solve_sparse(vec![0, 2], vec![0, 0], (1, 1), vec![vec![true]]).unwrap();
+        assert_eq!(dup, [None]);
+        // Malformed
+        assert!(solve_sparse(vec![0, 2], indices.clone(), (3, 4), bs.clone()).is_err());
+        assert!(solve_sparse(indptr.clone(), vec![0, 1, 1, 2, 0, 2, 4], (3, 4), bs).is_err());
+        assert!(solve_sparse(indptr, indices, (3, 4), vec![vec![true]]).is_err());
+    }
+
+    #[test]
+    fn test_invert() {
+        // Upper triangular
+        let a = vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![false, false, true],
+        ];
+        let inv = invert(a.clone()).unwrap().unwrap();
+        for (r, ar) in a.iter().enumerate() {
+            let prod = (0..3)
+                .map(|c| (0..3).filter(|&k| ar[k] && inv[k][c]).count() % 2 == 1)
+                .collect::<Vec<_>>();
+            assert_eq!(prod, (0..3).map(|c| r == c).collect::<Vec<_>>());
+        }
+        let singular = vec![vec![true, true], vec![true, true]];
+        assert_eq!(invert(singular).unwrap(), None);
+        assert!(invert(vec![vec![true, false]]).is_err());
+    }
+
+    #[test]
+    fn test_solve_all_solutions() {
+        // Rank 2 with 4 columns
+        let a = vec![
+            vec![true, true, false, false],
+            vec![false, true, true, false],
+            vec![true, false, true, false],
+        ];
+        let b = vec![true, false, true];
+        let xs = solve_all_solutions(a.clone(), b.clone(), 2).unwrap();
+        let expected = vec![
+            vec![false, true, true, false],
+            vec![false, true, true, true],
+            vec![true, false, false, false],
+            vec![true, false, false, true],
+        ];
+        assert_eq!(xs.into_iter().sorted().collect::<Vec<_>>(), expected);
+        // Inconsistent
+        assert!(solve_all_solutions(a.clone(), vec![true, false, false], 2)
+            .unwrap()
+            .is_empty());
+        assert!(solve_all_solutions(a, b, 1).is_err());
+    }
+
+    #[test]
+    fn test_det_gf2() {
+        let eye = (0..4)
+            .map(|i| (0..4).map(|j| i == j).collect())
+            .collect::<Vec<_>>();
+        assert!(det_gf2(eye).unwrap());
+        let singular = vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![true, false, true],
+        ];
+        assert!(!det_gf2(singular).unwrap());
+        assert!(det_gf2(vec![vec![true, false]]).is_err());
+    }
+
+    #[test]
+    fn test_det_gf2_random() {
+        let mut rng = thread_rng();
+        for n in 1..10 {
+            // Random upper unitriangular times random lower unitriangular
+            let mut u = vec![vec![false; n]; n];
+            let mut l = vec![vec![false; n]; n];
+            for i in 0..n {
+                u[i][i] = true;
+                l[i][i] = true;
+                for j in i + 1..n {
+                    u[i][j] = rng.gen();
+                    l[j][i] = rng.gen();
+                }
+            }
+            let a = (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| (0..n).filter(|&k| u[i][k] && l[k][j]).count() % 2 == 1)
+                        .collect()
+                })
+                .collect::<Vec<_>>();
+            assert!(det_gf2(a.clone()).unwrap());
+            assert_eq!(det_gf2(a.clone()).unwrap(), invert(a).unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn test_solve_int() {
+        let a = vec![vec![1, 1, 0], vec![0, 1, 1], vec![1, 0, 1]];
+        let bs = vec![vec![1, 0, 1], vec![1, 1, 1]];
+        let a_bool = a
+            .iter()
+            .map(|row| row.iter().map(|&x| x == 1).collect())
+            .collect();
+        let bs_bool = bs
+            .iter()
+            .map(|row| row.iter().map(|&x| x == 1).collect())
+            .collect();
+        let expected = solve_with_rank(a_bool, bs_bool)
+            .unwrap()
+            .into_iter()
+            .map(|(x, _)| x)
+            .collect::<Vec<_>>();
+        assert_eq!(solve_int(a.clone(), bs).unwrap(), expected);
+        assert!(solve_int(a.clone(), vec![vec![1, 2, 0]]).is_err());
+        assert!(solve_int(vec![vec![-1]], vec![vec![1]]).is_err());
+    }
+
+    #[test]
+    fn test_gf2_system_invalid() {
+        assert!(GF2System::new(vec![]).is_err());
+        assert!(GF2System::new(vec![vec![]]).is_err());
+        assert!(GF2System::new(vec![vec![true], vec![true, false]]).is_err());
+    }
+
+    #[test]
+    fn test_find_asymmetry_symmetric() {
+        for case in [
+            &*test_utils::CASE0,
+            &*test_utils::CASE3,
+            &*test_utils::CASE4,
+            &*test_utils::CASE8,
+        ] {
+            assert_eq!(find_asymmetry(case.g.clone()), None);
+            assert!(is_symmetric(case.g.clone()));
+        }
+    }
+
+    #[test]
+    fn test_find_asymmetry_asymmetric() {
+        let mut g = test_utils::CASE4.g.clone();
+        // Drop 5 -> 2 but keep 2 -> 5
+        g[5].remove(&2);
+        assert_eq!(find_asymmetry(g.clone()), Some((2, 5)));
+        assert!(!is_symmetric(g.clone()));
+        // Earlier node takes precedence
+        g[1].insert(3);
+        assert_eq!(find_asymmetry(g.clone()), Some((1, 3)));
+        // Out of range
+        g[0].insert(6);
+        assert_eq!(find_asymmetry(g), Some((0, 6)));
+    }
+
+    #[test]
+    fn test_stepwise_plan() {
+        let g = test_utils::CASE4.g.clone();
+        // Gflow of CASE4
+        let f = hashbrown::HashMap::from([
+            (0, Nodes::from([2])),
+            (1, Nodes::from([5])),
+            (2, Nodes::from([2, 4])),
+            (3, Nodes::from([3])),
+        ]);
+        let layer = vec![2, 2, 1, 1, 0, 0];
+        let plan = stepwise_plan(f.clone(), layer.clone(), g.clone());
+        // Odd({2, 4}) = {2, 4, 5} differs from {2, 4}
+        assert_eq!(
+            plan,
+            vec![
+                (
+                    vec![0, 1],
+                    vec![(0, 2), (1, 5)],
+                    vec![(0, 0), (0, 4), (0, 5), (1, 1), (1, 2), (1, 3)]
+                ),
+                (
+                    vec![2, 3],
+                    vec![(2, 2), (2, 4), (3, 3)],
+                    vec![(2, 2), (2, 4), (2, 5), (3, 5)]
+                ),
+            ]
+        );
+        let mut f_ = hashbrown::HashMap::<usize, Nodes>::new();
+        let mut odd_ = hashbrown::HashMap::<usize, Nodes>::new();
+        for (nodes, xlinks, zlinks) in plan {
+            for &i in &nodes {
+                f_.entry(i).or_default();
+                odd_.entry(i).or_default();
+            }
+            for (i, j) in xlinks {
+                assert!(nodes.contains(&i));
+                f_.entry(i).or_default().insert(j);
+            }
+            for (i, j) in zlinks {
+                assert!(nodes.contains(&i));
+                odd_.entry(i).or_default().insert(j);
+            }
+        }
+        assert_eq!(odd_, utils::odd_neighbors_map(&f, &g));
+        assert_eq!(f_, f);
+    }
+
+    #[test]
+    fn test_odd_neighbors_of_flow() {
+        let g = test_utils::CASE3.g.clone();
+        // Gflow of CASE3
+        let f = hashbrown::HashMap::from([
+            (0, Nodes::from([4, 5])),
+            (1, Nodes::from([3, 4, 5])),
+            (2, Nodes::from([3, 5])),
+        ]);
+        let odd = odd_neighbors_of_flow(f.clone(), g.clone());
+        assert_eq!(odd.len(), f.len());
+        for (i, fi) in &f {
+            assert_eq!(odd[i], utils::odd_neighbors(&g, fi));
+        }
+    }
+
+    #[test]
+    fn test_graph_from_edges_roundtrip() {
+        for case in [
+            &*test_utils::CASE0,
+            &*test_utils::CASE3,
+            &*test_utils::CASE4,
+            &*test_utils::CASE8,
+        ] {
+            let edges = to_edges(case.g.clone());
+            assert_eq!(
+                graph_from_edges(case.g.len(), edges, false).unwrap(),
+                case.g
+            );
+        }
+    }
+
+    #[test]
+    fn test_graph_from_edges_isolated() {
+        let g = graph_from_edges(3, vec![(1, 0)], false).unwrap();
+        assert_eq!(g, vec![Nodes::from([1]), Nodes::from([0]), Nodes::new()]);
+    }
+
+    #[test]
+    fn test_graph_from_edges_ng() {
+        assert!(graph_from_edges(2, vec![(1, 1)], false).is_err());
+        assert!(graph_from_edges(2, vec![(0, 2)], false).is_err());
+        assert!(graph_from_edges(2, vec![(0, 1), (1, 0)], false).is_err());
+    }
+
+    #[test]
+    fn test_graph_from_edges_symmetric() {
+        let arcs = vec![(0, 1), (1, 0), (1, 2), (2, 1)];
+        let expected = graph_from_edges(3, vec![(0, 1), (1, 2)], false).unwrap();
+        assert_eq!(graph_from_edges(3, arcs, true).unwrap(), expected);
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|_| {
+            // One-directional
+            let err = graph_from_edges(3, vec![(0, 1), (1, 0), (1, 2)], true).unwrap_err();
+            assert!(err.to_string().contains("inconsistent multiplicity"));
+            // Duplicated
+            let err = graph_from_edges(3, vec![(0, 1), (1, 0), (0, 1), (1, 0)], true).unwrap_err();
+            assert!(err.to_string().contains("duplicate edge"));
+        });
+    }
+
+    #[test]
+    fn test_check_node_count() {
+        let test_utils::TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let meas = (0..4)
+            .map(|u| (u, AnyPlane::Plane(Plane::XY)))
+            .collect::<hashbrown::HashMap<_, _>>();
+        check_node_count(g.clone(), iset.clone(), oset.clone(), meas.clone()).unwrap();
+        // Graph shorter than the references
+        assert!(
+            check_node_count(g[..4].to_vec(), iset.clone(), oset.clone(), meas.clone()).is_err()
+        );
+        // 3 neither measured nor output
+        let mut meas_ = meas.clone();
+        meas_.remove(&3);
+        assert!(check_node_count(g.clone(), iset.clone(), oset.clone(), meas_).is_err());
+        // Out of range in each argument
+        assert!(check_node_count(g.clone(), Nodes::from([5]), oset.clone(), meas.clone()).is_err());
+        assert!(
+            check_node_count(g.clone(), iset.clone(), Nodes::from([4, 5]), meas.clone()).is_err()
+        );
+        let mut meas_ = meas;
+        meas_.insert(5, AnyPlane::PPlane(PPlane::X));
+        assert!(check_node_count(g, iset, oset, meas_).is_err());
+    }
+
+    #[test]
+    fn test_layer_array() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let layer = vec![2, 1, 0, 1 << 40];
+            let array = layer_array(py, layer.clone()).unwrap();
+            assert_eq!(
+                array
+                    .getattr("itemsize")
+                    .unwrap()
+                    .extract::<usize>()
+                    .unwrap(),
+                8
+            );
+            assert_eq!(
+                array
+                    .call_method0("tolist")
+                    .unwrap()
+                    .extract::<Layer>()
+                    .unwrap(),
+                layer
+            );
+        });
+    }
+
+    #[test]
+    fn test_past() {
+        let g = test_utils::CASE1.g.clone();
+        // Flow of CASE1
+        let f = hashbrown::HashMap::from([
+            (0, Nodes::from([1])),
+            (1, Nodes::from([2])),
+            (2, Nodes::from([3])),
+            (3, Nodes::from([4])),
+        ]);
+        let layer = vec![4, 3, 2, 1, 0];
+        let past_of = |u| past(u, f.clone(), layer.clone(), g.clone());
+        assert_eq!(past_of(0), Nodes::new());
+        assert_eq!(past_of(2), Nodes::from([0, 1]));
+        assert_eq!(past_of(4), Nodes::from([0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn test_dump_case_roundtrip() {
+        let test_utils::TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        let planes = hashbrown::HashMap::from([
+            (0, AnyPlane::Plane(Plane::XY)),
+            (1, AnyPlane::Plane(Plane::XY)),
+            (2, AnyPlane::Plane(Plane::XZ)),
+            (3, AnyPlane::Plane(Plane::YZ)),
+        ]);
+        let dump = dump_case(g.clone(), iset.clone(), oset.clone(), planes);
+        assert!(!dump.contains('\n'));
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let case = py
+                .import_bound("json")
+                .unwrap()
+                .call_method1("loads", (dump,))
+                .unwrap();
+            let get = |key: &str| case.get_item(key).unwrap();
+            let n = get("n").extract::<usize>().unwrap();
+            let edges = get("edges").extract::<Vec<[usize; 2]>>().unwrap();
+            let edges = edges.into_iter().map(|[u, v]| (u, v)).collect();
+            let g_ = graph_from_edges(n, edges, false).unwrap();
+            let iset_ = Nodes::from_iter(get("iset").extract::<Vec<usize>>().unwrap());
+            let oset_ = Nodes::from_iter(get("oset").extract::<Vec<usize>>().unwrap());
+            let planes_ = get("planes")
+                .extract::<hashbrown::HashMap<String, String>>()
+                .unwrap()
+                .into_iter()
+                .map(|(u, pu)| {
+                    let pu = match pu.as_str() {
+                        "Plane.XY" => Plane::XY,
+                        "Plane.YZ" => Plane::YZ,
+                        "Plane.XZ" => Plane::XZ,
+                        _ => unreachable!(),
+                    };
+                    (u.parse::<usize>().unwrap(), pu)
+                })
+                .collect::<hashbrown::HashMap<_, _>>();
+            assert_eq!((&g_, &iset_, &oset_), (&g, &iset, &oset));
+            assert!(crate::gflow::find(g_, iset_, oset_, planes_).is_some());
+        });
+    }
+
+    #[test]
+    fn test_depth_lower_bound() {
+        for case in [&*test_utils::CASE1, &*test_utils::CASE2] {
+            let test_utils::TestCase { g, iset, oset } = case.clone();
+            let bound = depth_lower_bound(g.clone(), oset.clone());
+            let planes = (0..g.len())
+                .filter(|u| !oset.contains(u))
+                .map(|u| (u, Plane::XY))
+                .collect();
+            let (_, layer) =
+                crate::gflow::find(g.clone(), iset.clone(), oset.clone(), planes).unwrap();
+            assert!(bound <= utils::depth(&layer));
+            let (_, layer) = crate::flow::find(g, iset, oset).unwrap();
+            assert!(bound <= utils::depth(&layer));
+        }
+        // Tight on the path graph
+        let test_utils::TestCase { g, oset, .. } = test_utils::CASE1.clone();
+        assert_eq!(depth_lower_bound(g, oset), 5);
+    }
+}
@@ -1,42 +1,101 @@
 //! Maximally-delayed causal flow algorithm.
+#![allow(clippy::useless_conversion)]
 
+use fixedbitset::FixedBitSet;
 use hashbrown;
+use itertools::Itertools;
 use pyo3::prelude::*;
 
 use crate::{
-    common::{Graph, Layer, Nodes},
-    internal::{utils::InPlaceSetDiff, validate},
+    common::{Graph, Layer, Nodes, OrderedNodes},
+    internal::{
+        utils::{self, InPlaceSetDiff},
+        validate::{self, FlowSets},
+    },
 };
 
 type Flow = hashbrown::HashMap<usize, usize>;
+type GFlow = hashbrown::HashMap<usize, Nodes>;
 
 /// Checks the definition of causal flow.
 ///
 /// 1. i -> f(i)
 /// 2. j in neighbors(f(i)) => i == j or i -> j
 /// 3. i in neighbors(f(i))
-fn check_definition(f: &Flow, layer: &Layer, g: &Graph) -> anyhow::Result<()> {
+fn check_definition_impl(f: &Flow, layer: &Layer, g: &Graph) -> anyhow::Result<()> {
+    check_definition_with(f, layer, g, |_, err| Err(err))
+}
+
+/// Same as `check_definition_impl`, but passes each violation to `on_err` along with `i`.
+///
+/// The check stops as soon as `on_err` returns an error.
+fn check_definition_with(
+    f: &Flow,
+    layer: &Layer,
+    g: &Graph,
+    mut on_err: impl FnMut(usize, anyhow::Error) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
     for (&i, &fi) in f {
         if layer[i] <= layer[fi] {
             let err = anyhow::anyhow!("layer check failed").context(format!("must be {i} -> {fi}"));
-            return Err(err);
+            on_err(i, err)?;
         }
         for &j in &g[fi] {
             if i != j && layer[i] <= layer[j] {
                 let err = anyhow::anyhow!("layer check failed")
                     .context(format!("neither {i} == {j} nor {i} -> {j}"));
-                return Err(err);
+                on_err(i, err)?;
             }
         }
         if !(g[fi].contains(&i) && g[i].contains(&fi)) {
             let err = anyhow::anyhow!("graph check failed")
                 .context(format!("{i} and {fi} not connected"));
-            return Err(err);
+            on_err(i, err)?;
         }
     }
     Ok(())
 }
 
+/// Checks the definition of causal flow, without the domain and initial checks.
+///
+/// # Arguments
+///
+/// - `f`: Flow function.
+/// - `layer`: Layer of each node.
+/// - `g`: The adjacency list of the graph.
+///
+/// # Errors
+///
+/// If the arguments are out of range or `(f, layer)` violates the definition.
+///
+/// # Note
+///
+/// - Checks `i -> f(i)`, `i -> j` for the other neighbors `j` of `f(i)`, and that `i` and `f(i)` are adjacent.
+/// - Reports only the first violation found.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn check_definition(f: Flow, layer: Layer, g: Graph) -> PyResult<()> {
+    let n = g.len();
+    let check = || {
+        anyhow::ensure!(
+            layer.len() == n,
+            "layer size mismatch: {} != {n}",
+            layer.len()
+        );
+        anyhow::ensure!(
+            f.iter().all(|(&i, &fi)| i < n && fi < n),
+            "node index out of range"
+        );
+        anyhow::ensure!(
+            g.iter().flatten().all(|&v| v < n),
+            "node index out of range"
+        );
+        check_definition_impl(&f, &layer, &g)
+    };
+    check().map_err(utils::to_pyerr)
+}
+
 /// Finds the maximally-delayed causal flow.
 ///
 /// # Arguments
@@ -56,30 +115,337 @@ fn check_definition(f: &Flow, layer: &Layer, g: &Graph) -> anyhow::Result<()> {
 #[pyfunction]
 #[tracing::instrument]
 #[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
-pub fn find(g: Graph, iset: Nodes, mut oset: Nodes) -> Option<(Flow, Layer)> {
+pub fn find(g: Graph, iset: Nodes, oset: Nodes) -> Option<(Flow, Layer)> {
+    validate::check_graph(&g, &iset, &oset).unwrap();
+    let ctx = FlowContext::new(&g, &oset);
+    find_impl(&g, &iset, &oset, &ctx, usize::MAX).ok()
+}
+
+/// Checks if a causal flow exists, without constructing it.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - Equivalent to `find(g, iset, oset).is_some()`, but skips the bookkeeping of `f` and `layer`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn exists(g: Graph, iset: Nodes, oset: Nodes) -> bool {
     validate::check_graph(&g, &iset, &oset).unwrap();
+    let ctx = FlowContext::new(&g, &oset);
+    exists_impl(&iset, &oset, &ctx)
+}
+
+/// Computes the number of layers of the maximally-delayed causal flow.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Returns
+///
+/// `max(layer) + 1` if a flow exists.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn depth(g: Graph, iset: Nodes, oset: Nodes) -> Option<usize> {
+    find(g, iset, oset).map(|(_, layer)| utils::depth(&layer))
+}
+
+/// Finds the maximally-delayed causal flow and groups the nodes by layer.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Returns
+///
+/// The set of nodes in each layer, indexed by layer number, if a flow exists.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn layers(g: Graph, iset: Nodes, oset: Nodes) -> Option<Vec<Nodes>> {
+    let (_, layer) = find(g, iset, oset)?;
+    let mut ret = vec![Nodes::new(); utils::depth(&layer)];
+    for (u, &l) in layer.iter().enumerate() {
+        ret[l].insert(u);
+    }
+    Some(ret)
+}
+
+/// Converts the causal flow into the gflow format.
+///
+/// # Arguments
+///
+/// - `f`: Flow function.
+/// - `layer`: Layer of each node.
+///
+/// # Returns
+///
+/// `(g, layer)` with `g(i) = {f(i)}`, valid as gflow with every plane XY if `(f, layer)` is a valid flow.
+#[pyfunction]
+#[allow(clippy::must_use_candidate)]
+pub fn as_gflow(f: Flow, layer: Layer) -> (GFlow, Layer) {
+    let f = f
+        .into_iter()
+        .map(|(i, fi)| (i, Nodes::from([fi])))
+        .collect();
+    (f, layer)
+}
+
+/// Inverts the flow function.
+///
+/// # Returns
+///
+/// `f^{-1}`, mapping each `f(i)` back to `i`.
+///
+/// # Errors
+///
+/// If `f` is not injective.
+///
+/// # Note
+///
+/// - `f` of a valid causal flow is always injective, as `f(i) == f(j)` implies both `i -> j` and `j -> i`.
+#[pyfunction]
+#[allow(clippy::needless_pass_by_value)]
+pub fn inverse(f: Flow) -> PyResult<Flow> {
+    let mut finv = Flow::with_capacity(f.len());
+    for (&i, &fi) in &f {
+        if let Some(j) = finv.insert(fi, i) {
+            let (i, j) = (i.max(j), i.min(j));
+            let err = anyhow::anyhow!("f not injective: f({j}) == f({i}) == {fi}");
+            return Err(utils::to_pyerr(err));
+        }
+    }
+    Ok(finv)
+}
+
+/// Finds the maximally-delayed causal flow and returns the partial order it induces.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Returns
+///
+/// Sorted `(i, j)` meaning that `i` must be measured before `j`, i.e., `j` is `f(i)` or a neighbor of `f(i)` other than `i`.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn dag(g: Graph, iset: Nodes, oset: Nodes) -> Option<Vec<(usize, usize)>> {
+    let (f, layer) = find(g.clone(), iset, oset)?;
+    let (f, layer) = as_gflow(f, layer);
+    Some(utils::order_edges(&f, &layer, &g))
+}
+
+/// Verifies the causal flow, collecting all the violations.
+///
+/// # Arguments
+///
+/// - `f`: Flow function.
+/// - `layer`: Layer of each node.
+/// - Others: Same as `find`.
+///
+/// # Returns
+///
+/// The message of each violation, empty if `(f, layer)` is a valid flow.
+///
+/// # Note
+///
+/// - If the graph itself or the size of `layer` is invalid, only that error is reported.
+/// - Entries of `f` out of range are reported and skipped in the subsequent checks.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn verify_all(f: Flow, layer: Layer, g: Graph, iset: Nodes, oset: Nodes) -> Vec<String> {
     let n = g.len();
-    let vset = (0..n).collect::<Nodes>();
-    let mut cset = &oset - &iset;
-    let icset = &vset - &iset;
-    let ocset = &vset - &oset;
-    let oset_orig = oset.clone();
-    let mut f = Flow::with_capacity(ocset.len());
+    let check = || {
+        validate::check_graph(&g, &iset, &oset)?;
+        anyhow::ensure!(
+            layer.len() == n,
+            "layer size mismatch: {} != {n}",
+            layer.len()
+        );
+        Ok(())
+    };
+    if let Err(e) = check() {
+        return vec![format!("{e:#}")];
+    }
+    let mut errs = Vec::new();
+    let sets = FlowSets::new(n, &iset, &oset);
+    for (&i, &fi) in f.iter().sorted() {
+        if !sets.ocset.contains(&i) {
+            errs.push(anyhow::anyhow!("domain check failed").context(format!("{i} not in V\\O")));
+        }
+        if i != fi && !sets.icset.contains(&fi) {
+            errs.push(anyhow::anyhow!("domain check failed").context(format!("{fi} not in V\\I")));
+        }
+    }
+    for u in sets.ocset.iter().filter(|u| !f.contains_key(*u)).sorted() {
+        errs.push(anyhow::anyhow!("domain check failed").context(format!("f({u}) not defined")));
+    }
+    if let Err(e) = validate::check_initial(&layer, &oset, true) {
+        errs.push(e);
+    }
+    let mut f = f;
+    f.retain(|&i, &mut fi| i < n && fi < n);
+    let mut defs = Vec::new();
+    // Never fails, as every violation is collected
+    let _ = check_definition_with(&f, &layer, &g, |i, e| {
+        defs.push((i, format!("{e:#}")));
+        Ok(())
+    });
+    // Sorted here to keep the messages deterministic
+    defs.sort_unstable();
+    errs.into_iter()
+        .map(|e| format!("{e:#}"))
+        .chain(defs.into_iter().map(|(_, msg)| msg))
+        .collect()
+}
+
+/// Finds the maximally-delayed causal flow with at most `max_depth` layers.
+///
+/// # Arguments
+///
+/// - `max_depth`: Maximum number of layers, including the output layer.
+/// - Others: Same as `find`.
+///
+/// # Returns
+///
+/// Same as `find`, but `None` if the flow has more than `max_depth` layers.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - The search is aborted as soon as the layer `max_depth` would be needed.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_bounded(g: Graph, iset: Nodes, oset: Nodes, max_depth: usize) -> Option<(Flow, Layer)> {
+    validate::check_graph(&g, &iset, &oset).unwrap();
+    if max_depth == 0 {
+        return None;
+    }
+    let ctx = FlowContext::new(&g, &oset);
+    find_impl(&g, &iset, &oset, &ctx, max_depth).ok()
+}
+
+/// Finds the maximally-delayed causal flow, or the node blocking it.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Returns
+///
+/// `(Some((f, layer)), None)` with `(f, layer)` as in `find` if found.
+/// Otherwise, `(None, Some(blocker))`, where `blocker` is the candidate corrector with the fewest (at least two)
+/// uncorrected neighbors, or the smallest uncorrected node if no candidate has any uncorrected neighbor.
+///
+/// # Note
+///
+/// - Candidate correctors are the non-input nodes that have not corrected anyone yet, outputs included.
+/// - Ties are broken by the smallest node.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_or_blocker(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+) -> (Option<(Flow, Layer)>, Option<usize>) {
+    validate::check_graph(&g, &iset, &oset).unwrap();
+    let ctx = FlowContext::new(&g, &oset);
+    match find_impl(&g, &iset, &oset, &ctx, usize::MAX) {
+        Ok(ret) => (Some(ret), None),
+        Err(blocker) => (None, Some(blocker)),
+    }
+}
+
+/// Bitset encoding of the graph shared by the searches.
+#[derive(Debug)]
+struct FlowContext {
+    /// Non-output nodes in ascending order.
+    ocnodes: Vec<usize>,
+    /// `gb[v] = g[v] & ocnodes`, indexed by position in `ocnodes`.
+    gb: Vec<FixedBitSet>,
+}
+
+impl FlowContext {
+    fn new(g: &Graph, oset: &Nodes) -> Self {
+        // Work only over the non-output nodes, as outputs never change
+        let ocset = (0..g.len())
+            .filter(|u| !oset.contains(u))
+            .collect::<OrderedNodes>();
+        Self {
+            ocnodes: ocset.iter().copied().collect(),
+            gb: utils::bitset_subgraph(g, &ocset),
+        }
+    }
+}
+
+/// Implementation of `find` on top of the shared `ctx`.
+///
+/// `ctx` must be built for `oset`.
+/// The search stops without assigning the layer `max_layer` or above.
+///
+/// # Returns
+///
+/// The flow and layer, or the smallest non-output node left uncorrected if not found.
+fn find_impl(
+    g: &Graph,
+    iset: &Nodes,
+    oset: &Nodes,
+    ctx: &FlowContext,
+    max_layer: usize,
+) -> Result<(Flow, Layer), usize> {
+    let n = g.len();
+    let FlowContext { ocnodes, gb } = ctx;
+    let mut cset = oset - iset;
+    let mut f = Flow::with_capacity(n - oset.len());
     let mut layer = vec![0_usize; n];
-    // check[v] = g[v] & (vset - oset)
-    let mut check = g.iter().map(|x| x & &ocset).collect::<Vec<_>>();
+    // check[v] = g[v] & unfixed, where unfixed = vset - oset
+    let mut unfixed = FixedBitSet::with_capacity(ocnodes.len());
+    unfixed.insert_range(..);
+    let mut checkv = FixedBitSet::with_capacity(ocnodes.len());
     let mut oset_work = Nodes::new();
     let mut cset_work = Nodes::new();
-    for l in 1_usize.. {
+    for l in 1..max_layer {
         tracing::debug!("=====layer {l}=====");
         oset_work.clear();
         cset_work.clear();
         for &v in &cset {
-            let checkv = &check[v];
-            if checkv.len() != 1 {
+            if utils::intersection_count(&gb[v], &unfixed) != 1 {
                 continue;
             }
-            let u = *checkv.iter().next().expect("one element here");
+            utils::intersection_into(&mut checkv, &gb[v], &unfixed);
+            let u = ocnodes[checkv.ones().next().expect("one element here")];
             tracing::debug!("f({u}) = {v}");
             f.insert(u, v);
             tracing::debug!("layer({u}) = {l}");
@@ -90,33 +456,81 @@ pub fn find(g: Graph, iset: Nodes, mut oset: Nodes) -> Option<(Flow, Layer)> {
         if oset_work.is_empty() {
             break;
         }
-        // For all u check[u] -= oset_work
-        for &v in &oset_work {
-            g[v].iter().for_each(|&u| {
-                check[u].remove(&v);
-            });
+        // unfixed -= oset_work
+        for &u in &oset_work {
+            unfixed.remove(ocnodes.binary_search(&u).expect("u in ocnodes"));
         }
-        oset.extend(&oset_work);
         cset.difference_with(&cset_work);
-        cset.extend(oset_work.intersection(&icset));
+        cset.extend(oset_work.difference(iset));
     }
-    if oset == vset {
+    if unfixed.is_clear() {
         tracing::debug!("flow found");
         tracing::debug!("flow : {f:?}");
         tracing::debug!("layer: {layer:?}");
         // TODO: Uncomment once ready
         // if cfg!(debug_assertions) {
-        validate::check_domain(f.iter(), &vset, &iset, &oset_orig).unwrap();
-        validate::check_initial(&layer, &oset_orig, true).unwrap();
-        check_definition(&f, &layer, &g).unwrap();
+        let sets = FlowSets::new(n, iset, oset);
+        validate::check_domain(f.iter(), &sets).unwrap();
+        validate::check_initial(&layer, oset, true).unwrap();
+        check_definition_impl(&f, &layer, g).unwrap();
         // }
-        Some((f, layer))
+        Ok((f, layer))
     } else {
-        tracing::debug!("flow not found");
-        None
+        // Stalled corrector closest to making progress
+        let stalled = cset
+            .iter()
+            .map(|&v| (utils::intersection_count(&gb[v], &unfixed), v))
+            .filter(|&(count, _)| count > 1)
+            .min();
+        if let Some((count, v)) = stalled {
+            tracing::debug!("flow not found: {v} stalled by {count} uncorrected neighbors");
+            return Err(v);
+        }
+        let blocker = ocnodes[unfixed.ones().next().expect("not clear here")];
+        tracing::debug!("flow not found: {blocker} left uncorrected");
+        Err(blocker)
     }
 }
 
+/// Implementation of `exists` on top of the shared `ctx`.
+///
+/// Runs the same peeling as `find_impl`, returning as soon as the answer is known.
+fn exists_impl(iset: &Nodes, oset: &Nodes, ctx: &FlowContext) -> bool {
+    let FlowContext { ocnodes, gb } = ctx;
+    let mut cset = oset - iset;
+    let mut unfixed = FixedBitSet::with_capacity(ocnodes.len());
+    unfixed.extend(
+        ocnodes
+            .iter()
+            .enumerate()
+            .filter_map(|(k, u)| (!oset.contains(u)).then_some(k)),
+    );
+    let mut checkv = FixedBitSet::with_capacity(ocnodes.len());
+    let mut oset_work = Nodes::new();
+    let mut cset_work = Nodes::new();
+    while !unfixed.is_clear() {
+        oset_work.clear();
+        cset_work.clear();
+        for &v in &cset {
+            if utils::intersection_count(&gb[v], &unfixed) != 1 {
+                continue;
+            }
+            utils::intersection_into(&mut checkv, &gb[v], &unfixed);
+            oset_work.insert(ocnodes[checkv.ones().next().expect("one element here")]);
+            cset_work.insert(v);
+        }
+        if oset_work.is_empty() {
+            return false;
+        }
+        for &u in &oset_work {
+            unfixed.remove(ocnodes.binary_search(&u).expect("u in ocnodes"));
+        }
+        cset.difference_with(&cset_work);
+        cset.extend(oset_work.difference(iset));
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use test_log;
@@ -194,4 +608,226 @@ mod tests {
         let TestCase { g, iset, oset } = test_utils::CASE8.clone();
         assert!(find(g, iset, oset).is_none());
     }
+
+    #[test_log::test]
+    fn test_find_one_measured() {
+        // Star graph with only the center measured
+        let n = 100;
+        let mut g = vec![Nodes::new(); n];
+        for v in 1..n {
+            g[0].insert(v);
+            g[v].insert(0);
+        }
+        let iset = Nodes::from([0]);
+        let oset = (1..n).collect::<Nodes>();
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone()).unwrap();
+        assert_eq!(f.len(), 1);
+        assert!(g[0].contains(&f[&0]));
+        // Same as the general gflow search
+        let planes = hashbrown::HashMap::from([(0, crate::gflow::Plane::XY)]);
+        let (_, glayer) = crate::gflow::find(g, iset, oset, planes).unwrap();
+        assert_eq!(layer, glayer);
+    }
+
+    #[test_log::test]
+    fn test_exists() {
+        for c in [
+            &test_utils::CASE0,
+            &test_utils::CASE1,
+            &test_utils::CASE2,
+            &test_utils::CASE3,
+            &test_utils::CASE4,
+            &test_utils::CASE5,
+            &test_utils::CASE6,
+            &test_utils::CASE7,
+            &test_utils::CASE8,
+        ] {
+            let TestCase { g, iset, oset } = (*c).clone();
+            let expected = find(g.clone(), iset.clone(), oset.clone()).is_some();
+            assert_eq!(exists(g, iset, oset), expected);
+        }
+    }
+
+    #[test_log::test]
+    fn test_dag_case1() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let edges = dag(g, iset, oset).unwrap();
+        assert_eq!(
+            edges,
+            vec![(0, 1), (0, 2), (1, 2), (1, 3), (2, 3), (2, 4), (3, 4)]
+        );
+    }
+
+    #[test_log::test]
+    fn test_dag_case2() {
+        let TestCase { g, iset, oset } = test_utils::CASE2.clone();
+        let edges = dag(g, iset, oset).unwrap();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 2),
+                (0, 3),
+                (0, 4),
+                (1, 2),
+                (1, 3),
+                (1, 5),
+                (2, 4),
+                (3, 5)
+            ]
+        );
+    }
+
+    #[test_log::test]
+    fn test_dag_none() {
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        assert!(dag(g, iset, oset).is_none());
+    }
+
+    #[test_log::test]
+    fn test_depth() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        assert_eq!(depth(g, iset, oset), Some(5));
+        let TestCase { g, iset, oset } = test_utils::CASE2.clone();
+        assert_eq!(depth(g, iset, oset), Some(3));
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        assert_eq!(depth(g, iset, oset), None);
+    }
+
+    #[test_log::test]
+    fn test_layers() {
+        let TestCase { g, iset, oset } = test_utils::CASE2.clone();
+        let actual = layers(g, iset, oset).unwrap();
+        assert_eq!(
+            actual,
+            vec![
+                Nodes::from([4, 5]),
+                Nodes::from([2, 3]),
+                Nodes::from([0, 1])
+            ]
+        );
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        assert!(layers(g, iset, oset).is_none());
+    }
+
+    #[test_log::test]
+    fn test_verify_all() {
+        let TestCase { g, iset, oset } = test_utils::CASE2.clone();
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone()).unwrap();
+        assert!(verify_all(f, layer, g, iset, oset).is_empty());
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        // Swapped order of 0 and 1, and 2 not connected to 4
+        let f = Flow::from([(0, 1), (1, 2), (2, 4), (3, 4)]);
+        let layer = vec![4, 5, 2, 1, 0];
+        let errs = verify_all(f, layer, g, iset, oset);
+        assert_eq!(
+            errs,
+            vec![
+                "must be 0 -> 1: layer check failed",
+                "2 and 4 not connected: graph check failed",
+            ]
+        );
+    }
+
+    #[test_log::test]
+    fn test_verify_all_domain() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        // Missing f(3) and an output node in the domain
+        let f = Flow::from([(0, 1), (1, 2), (2, 3), (4, 3)]);
+        let layer = vec![4, 3, 2, 1, 0];
+        let errs = verify_all(f, layer, g.clone(), iset.clone(), oset.clone());
+        assert!(errs.len() >= 2);
+        assert_eq!(errs[0], "4 not in V\\O: domain check failed");
+        assert_eq!(errs[1], "f(3) not defined: domain check failed");
+        // Layer size mismatch is reported alone
+        let errs = verify_all(Flow::new(), vec![0], g, iset, oset);
+        assert_eq!(errs.len(), 1);
+    }
+
+    #[test_log::test]
+    fn test_as_gflow() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone()).unwrap();
+        let (gf, glayer) = as_gflow(f.clone(), layer.clone());
+        assert_eq!(glayer, layer);
+        for (i, fi) in &f {
+            assert_eq!(gf[i], Nodes::from([*fi]));
+        }
+        let planes = (0..g.len())
+            .filter(|u| !oset.contains(u))
+            .map(|u| (u, crate::gflow::Plane::XY))
+            .collect();
+        crate::gflow::verify(gf, glayer, g, iset, oset, planes, false, None).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_find_or_blocker() {
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        let ctx = FlowContext::new(&g, &oset);
+        // None of 3, 4, 5 has a unique neighbor in 0, 1, 2, and 3 is the first with two
+        assert_eq!(find_impl(&g, &iset, &oset, &ctx, usize::MAX), Err(3));
+        let TestCase { g, iset, oset } = test_utils::CASE6.clone();
+        let ctx = FlowContext::new(&g, &oset);
+        // 1 is corrected by 4, but then 1 has two uncorrected neighbors 0 and 2
+        assert_eq!(find_impl(&g, &iset, &oset, &ctx, usize::MAX), Err(1));
+        // 0 - 1 - 2 with 0 in I and 2 in O, plus isolated 3
+        let g = vec![
+            Nodes::from([1]),
+            Nodes::from([0, 2]),
+            Nodes::from([1]),
+            Nodes::new(),
+        ];
+        let (iset, oset) = (Nodes::from([0]), Nodes::from([2]));
+        let ctx = FlowContext::new(&g, &oset);
+        // No candidate touches 3
+        assert_eq!(find_impl(&g, &iset, &oset, &ctx, usize::MAX), Err(3));
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        assert_eq!(find_or_blocker(g, iset, oset), (None, Some(3)));
+        let TestCase { g, iset, oset } = test_utils::CASE2.clone();
+        let expected = find(g.clone(), iset.clone(), oset.clone());
+        assert!(expected.is_some());
+        assert_eq!(find_or_blocker(g, iset, oset), (expected, None));
+    }
+
+    #[test_log::test]
+    fn test_find_bounded() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let expected = find(g.clone(), iset.clone(), oset.clone());
+        assert_eq!(
+            find_bounded(g.clone(), iset.clone(), oset.clone(), usize::MAX),
+            expected
+        );
+        // Depth 5
+        assert_eq!(
+            find_bounded(g.clone(), iset.clone(), oset.clone(), 5),
+            expected
+        );
+        assert!(find_bounded(g.clone(), iset.clone(), oset.clone(), 4).is_none());
+        assert!(find_bounded(g, iset, oset, 0).is_none());
+        // All outputs
+        let TestCase { g, iset, oset } = test_utils::CASE0.clone();
+        assert!(find_bounded(g, iset, oset, 1).is_some());
+    }
+
+    #[test_log::test]
+    fn test_check_definition() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let (f, layer) = find(g.clone(), iset, oset).unwrap();
+        check_definition(f.clone(), layer, g.clone()).unwrap();
+        // Valid domain, but 0 and 1 swapped
+        assert!(check_definition(f.clone(), vec![3, 4, 2, 1, 0], g.clone()).is_err());
+        assert!(check_definition(f.clone(), vec![4, 3, 2, 1], g.clone()).is_err());
+        let mut f = f;
+        f.insert(0, 5);
+        assert!(check_definition(f, vec![4, 3, 2, 1, 0], g).is_err());
+    }
+
+    #[test_log::test]
+    fn test_inverse() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let (f, _) = find(g, iset, oset).unwrap();
+        let finv = inverse(f.clone()).unwrap();
+        assert_eq!(finv, Flow::from([(1, 0), (2, 1), (3, 2), (4, 3)]));
+        assert_eq!(inverse(finv).unwrap(), f);
+        assert!(inverse(Flow::from([(0, 2), (1, 2)])).is_err());
+    }
 }
@@ -1,17 +1,18 @@
 //! Maximally-delayed generalized flow algorithm.
+#![allow(clippy::useless_conversion)]
 
-use std::iter;
+use std::{iter, ops::Range, slice};
 
 use fixedbitset::FixedBitSet;
 use hashbrown;
 use pyo3::prelude::*;
 
 use crate::{
-    common::{Graph, Layer, Nodes, OrderedNodes},
+    common::{FindStats, Graph, Layer, Nodes, OrderedNodes},
     internal::{
         gf2_linalg::GF2Solver,
         utils::{self, InPlaceSetDiff},
-        validate,
+        validate::{self, FlowSets},
     },
 };
 
@@ -29,97 +30,475 @@ pub enum Plane {
 
 type Planes = hashbrown::HashMap<usize, Plane>;
 type GFlow = hashbrown::HashMap<usize, Nodes>;
+type AllowedPlanes = hashbrown::HashMap<usize, Vec<Plane>>;
+
+#[pyclass(frozen, get_all)]
+#[derive(Debug, Clone)]
+/// Gflow bundled with the data derived from it.
+pub struct FlowReport {
+    /// Gflow function.
+    pub f: GFlow,
+    /// Layer of each node.
+    pub layer: Layer,
+    /// Number of layers.
+    pub depth: usize,
+    /// `Odd(f(i))` of each node `i` in `f`.
+    pub odd: GFlow,
+    /// Ordering constraints `(i, j)`, meaning that `i` must be measured before `j`.
+    pub order: Vec<(usize, usize)>,
+    /// Rank of the linear system that determined `f(i)` of each node `i` in `f`.
+    pub rank: hashbrown::HashMap<usize, usize>,
+    /// Number of free variables in the linear system that determined `f(i)` of each node `i` in `f`.
+    pub nullity: hashbrown::HashMap<usize, usize>,
+    /// Solver statistics.
+    pub stats: FindStats,
+}
+
+/// Either flow or gflow function.
+#[derive(FromPyObject, Debug, Clone, PartialEq, Eq)]
+pub enum AnyFlow {
+    Flow(hashbrown::HashMap<usize, usize>),
+    GFlow(GFlow),
+}
+
+impl AnyFlow {
+    /// Converts to gflow, with `f(i) = {f(i)}` for flow.
+    fn into_gflow(self) -> GFlow {
+        match self {
+            Self::Flow(f) => f
+                .into_iter()
+                .map(|(i, fi)| (i, Nodes::from([fi])))
+                .collect(),
+            Self::GFlow(f) => f,
+        }
+    }
+}
+
+/// Checks the definition of gflow for a single node.
+///
+/// 1. i -> f(i)
+/// 2. j in Odd(f(i)) => i == j or i -> j
+/// 3. i not in f(i) and in Odd(f(i)) if plane(i) == XY
+/// 4. i in f(i) and in Odd(f(i)) if plane(i) == YZ
+/// 5. i in f(i) and not in Odd(f(i)) if plane(i) == XZ
+fn check_node(i: usize, fi: &Nodes, g: &Graph, layer: &Layer, pi: Plane) -> anyhow::Result<()> {
+    let odd_fi = utils::odd_neighbors(g, fi);
+    check_order(i, fi, &odd_fi, layer)?;
+    check_plane(i, pi, (fi.contains(&i), odd_fi.contains(&i)))
+}
+
+/// Checks the order conditions 1 and 2 of `check_node`.
+fn check_order(i: usize, fi: &Nodes, odd_fi: &Nodes, layer: &Layer) -> anyhow::Result<()> {
+    for &fij in fi {
+        if i != fij && layer[i] <= layer[fij] {
+            let err = anyhow::anyhow!("layer check failed")
+                .context(format!("neither {i} == {fij} nor {i} -> {fij}: fi"));
+            return Err(err);
+        }
+    }
+    for &j in odd_fi {
+        if i != j && layer[i] <= layer[j] {
+            let err = anyhow::anyhow!("layer check failed").context(format!(
+                "neither {i} == {j} nor {i} -> {j}: odd_neighbors(g, fi)"
+            ));
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Checks the plane conditions of `check_node`.
+///
+/// `in_info` is `(i in f(i), i in Odd(f(i)))`.
+fn check_plane(i: usize, pi: Plane, in_info: (bool, bool)) -> anyhow::Result<()> {
+    match pi {
+        Plane::XY if in_info != (false, true) => {
+            let err = anyhow::anyhow!("plane check failed").context(format!(
+                "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (false, true): XY"
+            ));
+            Err(err)
+        }
+        Plane::YZ if in_info != (true, false) => {
+            let err = anyhow::anyhow!("plane check failed").context(format!(
+                "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (true, false): YZ"
+            ));
+            Err(err)
+        }
+        Plane::XZ if in_info != (true, true) => {
+            let err = anyhow::anyhow!("plane check failed").context(format!(
+                "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (true, true): XZ"
+            ));
+            Err(err)
+        }
+        _ => Ok(()),
+    }
+}
 
 /// Checks the definition of gflow.
 ///
-/// 1. i -> g(i)
-/// 2. j in Odd(g(i)) => i == j or i -> j
-/// 3. i not in g(i) and in Odd(g(i)) if plane(i) == XY
-/// 4. i in g(i) and in Odd(g(i)) if plane(i) == YZ
-/// 5. i in g(i) and not in Odd(g(i)) if plane(i) == XZ
+/// See `check_node` for the conditions checked for each node.
 fn check_definition(f: &GFlow, layer: &Layer, g: &Graph, planes: &Planes) -> anyhow::Result<()> {
     anyhow::ensure!(
         f.len() == planes.len(),
         "f and planes must have the same codomain"
     );
     for (&i, fi) in f {
-        let pi = planes[&i];
+        let Some(&pi) = planes.get(&i) else {
+            anyhow::bail!("plane check failed: plane of {i} not specified");
+        };
+        check_node(i, fi, g, layer, pi)?;
+    }
+    Ok(())
+}
+
+/// Same as `check_definition`, but with `f(i)` and `Odd(f(i))` encoded as bitsets.
+///
+/// # Note
+///
+/// - Computes `Odd(f(i))` by toggling bits instead of hashing, reusing two `n`-bit buffers for all the nodes.
+/// - About 3x faster than `check_definition` on a 60x30 grid graph.
+/// - `on_node` is called with the number of nodes checked so far after each node passes, and aborts the check on `Err`.
+fn check_definition_bitset(
+    f: &GFlow,
+    layer: &Layer,
+    g: &Graph,
+    planes: &Planes,
+    mut on_node: impl FnMut(usize) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        f.len() == planes.len(),
+        "f and planes must have the same codomain"
+    );
+    let n = g.len();
+    let mut fb = FixedBitSet::with_capacity(n);
+    let mut odd = FixedBitSet::with_capacity(n);
+    for (k, (&i, fi)) in f.iter().enumerate() {
+        let Some(&pi) = planes.get(&i) else {
+            anyhow::bail!("plane check failed: plane of {i} not specified");
+        };
+        fb.clear();
+        odd.clear();
         for &fij in fi {
-            if i != fij && layer[i] <= layer[fij] {
-                let err = anyhow::anyhow!("layer check failed")
-                    .context(format!("neither {i} == {fij} nor {i} -> {fij}: fi"));
-                return Err(err);
+            fb.insert(fij);
+            for &k in &g[fij] {
+                odd.toggle(k);
             }
         }
-        let odd_fi = utils::odd_neighbors(g, fi);
-        for &j in &odd_fi {
-            if i != j && layer[i] <= layer[j] {
-                let err = anyhow::anyhow!("layer check failed").context(format!(
-                    "neither {i} == {j} nor {i} -> {j}: odd_neighbors(g, fi)"
-                ));
-                return Err(err);
-            }
+        if let Some(fij) = fb.ones().find(|&j| i != j && layer[i] <= layer[j]) {
+            let err = anyhow::anyhow!("layer check failed")
+                .context(format!("neither {i} == {fij} nor {i} -> {fij}: fi"));
+            return Err(err);
         }
-        let in_info = (fi.contains(&i), odd_fi.contains(&i));
-        match pi {
-            Plane::XY if in_info != (false, true) => {
-                let err = anyhow::anyhow!("plane check failed").context(format!(
-                    "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (false, true): XY"
-                ));
-                return Err(err);
-            }
-            Plane::YZ if in_info != (true, false) => {
-                let err = anyhow::anyhow!("plane check failed").context(format!(
-                    "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (true, false): YZ"
-                ));
-                return Err(err);
-            }
-            Plane::XZ if in_info != (true, true) => {
-                let err = anyhow::anyhow!("plane check failed").context(format!(
-                    "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (true, true): XZ"
-                ));
-                return Err(err);
-            }
-            _ => {}
+        if let Some(j) = odd.ones().find(|&j| i != j && layer[i] <= layer[j]) {
+            let err = anyhow::anyhow!("layer check failed").context(format!(
+                "neither {i} == {j} nor {i} -> {j}: odd_neighbors(g, fi)"
+            ));
+            return Err(err);
         }
+        check_plane(i, pi, (fb.contains(i), odd.contains(i)))?;
+        on_node(k + 1)?;
     }
     Ok(())
 }
 
+/// Runs all the checks on the gflow `(f, layer)`.
+fn verify_impl(
+    f: &GFlow,
+    layer: &Layer,
+    g: &Graph,
+    iset: &Nodes,
+    oset: &Nodes,
+    planes: &Planes,
+) -> anyhow::Result<()> {
+    verify_impl_with(f, layer, g, iset, oset, planes, |_| Ok(()))
+}
+
+/// Same as `verify_impl`, but calls `on_node` as in `check_definition_bitset`.
+fn verify_impl_with(
+    f: &GFlow,
+    layer: &Layer,
+    g: &Graph,
+    iset: &Nodes,
+    oset: &Nodes,
+    planes: &Planes,
+    on_node: impl FnMut(usize) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    validate::check_graph(g, iset, oset)?;
+    let n = g.len();
+    anyhow::ensure!(layer.len() == n, "layer size mismatch");
+    let sets = FlowSets::new(n, iset, oset);
+    let f_flatiter = f
+        .iter()
+        .flat_map(|(i, fi)| Iterator::zip(iter::repeat(i), fi.iter()));
+    validate::check_domain(f_flatiter, &sets)?;
+    validate::check_initial(layer, oset, true)?;
+    check_definition_bitset(f, layer, g, planes, on_node)
+}
+
+/// Verifies the gflow.
+///
+/// # Arguments
+///
+/// - `f`: Gflow function.
+/// - `layer`: Layer of each node.
+/// - `tolerant`: Ignore the entries of `planes` for the output nodes instead of failing.
+/// - `progress`: Callback invoked as `progress(nodes_checked, total)` after each node in `f` passes.
+/// - Others: Same as `find`.
+///
+/// # Errors
+///
+/// If `(f, layer)` is not a valid gflow, or `progress` or the signal handlers raise.
+///
+/// # Note
+///
+/// - Even if `tolerant`, each non-output node needs both `f(i)` and `planes[i]`.
+/// - With `progress`, pending signals are checked before each call, so that `KeyboardInterrupt` stops the check.
+/// - Exceptions raised by `progress` or the signal handlers are propagated as is.
+#[pyfunction]
+#[pyo3(signature = (f, layer, g, iset, oset, planes, tolerant = false, progress = None))]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::too_many_arguments)]
+pub fn verify(
+    f: GFlow,
+    layer: Layer,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    mut planes: Planes,
+    tolerant: bool,
+    progress: Option<PyObject>,
+) -> PyResult<()> {
+    if tolerant {
+        planes.retain(|u, _| !oset.contains(u));
+    }
+    let Some(progress) = progress else {
+        return verify_impl(&f, &layer, &g, &iset, &oset, &planes).map_err(utils::to_pyerr);
+    };
+    let total = f.len();
+    let on_node = |k: usize| -> anyhow::Result<()> {
+        Python::with_gil(|py| {
+            py.check_signals()?;
+            progress.call1(py, (k, total))?;
+            Ok(())
+        })
+    };
+    verify_impl_with(&f, &layer, &g, &iset, &oset, &planes, on_node)
+        .map_err(|e| e.downcast::<PyErr>().unwrap_or_else(utils::to_pyerr))
+}
+
+/// Verifies the gflow without the layer.
+///
+/// # Arguments
+///
+/// - `f`: Gflow function.
+/// - Others: Same as `find`.
+///
+/// # Errors
+///
+/// If `f` induces a cyclic order or is not a valid gflow.
+///
+/// # Note
+///
+/// - The layer is recomputed from `f` as the one with the least number of layers.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn verify_corrections(
+    f: GFlow,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+) -> PyResult<()> {
+    let check = || {
+        validate::check_graph(&g, &iset, &oset)?;
+        let sets = FlowSets::new(g.len(), &iset, &oset);
+        let f_flatiter = f
+            .iter()
+            .flat_map(|(i, fi)| Iterator::zip(iter::repeat(i), fi.iter()));
+        validate::check_domain(f_flatiter, &sets)?;
+        let layer = utils::compute_layer(&f, &g)?;
+        // Graph and domain already checked
+        validate::check_initial(&layer, &oset, true)?;
+        check_definition_bitset(&f, &layer, &g, &planes, |_| Ok(()))
+    };
+    check().map_err(utils::to_pyerr)
+}
+
+/// Verifies the gflow and checks if `layer` is maximally delayed.
+///
+/// # Arguments
+///
+/// Same as `verify`.
+///
+/// # Errors
+///
+/// If `(f, layer)` is not a valid gflow or `layer` differs from the one of `find`.
+///
+/// # Note
+///
+/// - The maximally-delayed layer is unique, while `f` is not.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn verify_maximal(
+    f: GFlow,
+    layer: Layer,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+) -> PyResult<()> {
+    let check = || {
+        verify_impl(&f, &layer, &g, &iset, &oset, &planes)?;
+        let allowed = single_planes(&planes);
+        let (_, layer_max, _) = find_impl(&g, &iset, &oset, &allowed, &mut FindAux::default())
+            .ok_or_else(|| anyhow::anyhow!("maximality check failed: gflow not found"))?;
+        if let Some(u) = (0..g.len()).find(|&u| layer[u] != layer_max[u]) {
+            let err = anyhow::anyhow!("maximality check failed").context(format!(
+                "layer({u}) = {} but {} if maximally delayed",
+                layer[u], layer_max[u]
+            ));
+            return Err(err);
+        }
+        Ok(())
+    };
+    check().map_err(utils::to_pyerr)
+}
+
+/// Checks if `ci` is a valid correction set of `i` in isolation.
+///
+/// # Arguments
+///
+/// - `i`: The node to check.
+/// - `ci`: The candidate correction set of `i`.
+/// - `g`: The adjacency list of the graph.
+/// - `layer`: The layer of each node.
+/// - `plane`: The measurement plane of `i`.
+///
+/// # Errors
+///
+/// If the arguments are out of range or `ci` violates the gflow conditions for `i`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn check_node_correction(
+    i: usize,
+    ci: Nodes,
+    g: Graph,
+    layer: Layer,
+    plane: Plane,
+) -> PyResult<()> {
+    let n = g.len();
+    let check = || {
+        anyhow::ensure!(layer.len() == n, "layer size mismatch");
+        anyhow::ensure!(
+            i < n && ci.iter().all(|&c| c < n),
+            "node index out of range"
+        );
+        check_node(i, &ci, &g, &layer, plane)
+    };
+    check().map_err(utils::to_pyerr)
+}
+
+/// Checks if `layer` is a valid measurement order for `f`, ignoring the measurement planes.
+///
+/// # Arguments
+///
+/// - `f`: Flow or gflow function.
+/// - `layer`: Layer of each node, not necessarily maximally-delayed.
+/// - `g`: The adjacency list of the graph.
+///
+/// # Panics
+///
+/// If `layer` and `g` have different lengths or `f` contains unknown nodes.
+///
+/// # Note
+///
+/// - Checks only the conditions 1 and 2 of `check_node`, with `f(i) = {f(i)}` for flow.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn is_valid_order(f: AnyFlow, layer: Layer, g: Graph) -> bool {
+    assert_eq!(
+        layer.len(),
+        g.len(),
+        "layer and g must have the same length"
+    );
+    let f = f.into_gflow();
+    f.iter().all(|(&i, fi)| {
+        let odd_fi = utils::odd_neighbors(&g, fi);
+        check_order(i, fi, &odd_fi, &layer).is_ok()
+    })
+}
+
+/// Computes the total size of the corrections as a scalar cost.
+///
+/// # Arguments
+///
+/// - `f`: Flow or gflow function.
+/// - `g`: The adjacency list of the graph.
+///
+/// # Returns
+///
+/// Sum of `|f(i)| + |Odd(f(i))|` over the domain of `f`, with `f(i) = {f(i)}` for flow.
+///
+/// # Panics
+///
+/// If `f` contains unknown nodes.
+///
+/// # Note
+///
+/// - `i` itself is counted if in `f(i)` or `Odd(f(i))`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn correction_volume(f: AnyFlow, g: Graph) -> usize {
+    match f {
+        AnyFlow::Flow(f) => f.values().map(|&fi| 1 + g[fi].len()).sum(),
+        AnyFlow::GFlow(f) => f
+            .values()
+            .map(|fi| fi.len() + utils::odd_neighbors(&g, fi).len())
+            .sum(),
+    }
+}
+
 /// Initializes the working matrix.
+///
+/// Each element `(u, pu)` of `eqs` corresponds to one right-hand side, requesting to correct `u` in the plane `pu`.
+/// Each row corresponds to a node in `rowset`, which must not be in `Odd(f(u))` other than `u`.
 fn init_work(
     work: &mut [FixedBitSet],
     g: &Graph,
-    planes: &Planes,
-    ocset: &OrderedNodes,
+    eqs: &[(usize, Plane)],
+    rowset: &OrderedNodes,
     omiset: &OrderedNodes,
 ) {
     let ncols = omiset.len();
     // Set-to-index maps
-    let oc2i = utils::indexmap::<hashbrown::HashMap<_, _>>(ocset);
+    let oc2i = utils::indexmap::<hashbrown::HashMap<_, _>>(rowset);
     let omi2i = utils::indexmap::<hashbrown::HashMap<_, _>>(omiset);
-    // Encode node as one-hot vector
-    for (i, &u) in ocset.iter().enumerate() {
-        let gu = &g[u];
-        // Initialize adjacency matrix
-        let r = i;
-        for &v in gu {
+    // Initialize adjacency matrix
+    for (r, &u) in rowset.iter().enumerate() {
+        for &v in &g[u] {
             if let Some(&c) = omi2i.get(&v) {
                 work[r].insert(c);
             }
         }
+    }
+    // Encode node as one-hot vector
+    for (ieq, &(u, pu)) in eqs.iter().enumerate() {
         // Initialize rhs
-        let ieq = i;
         let c = ncols + ieq;
-        if let Plane::XY | Plane::XZ = planes[&u] {
+        if let Plane::XY | Plane::XZ = pu {
             // = u
-            work[ieq].insert(c);
+            work[oc2i[&u]].insert(c);
         }
-        if planes[&u] == Plane::XY {
+        if pu == Plane::XY {
             continue;
         }
         // Include u
-        for &v in gu {
+        for &v in &g[u] {
             if let Some(&r) = oc2i.get(&v) {
                 work[r].toggle(c);
             }
@@ -148,111 +527,1496 @@ fn init_work(
 #[tracing::instrument]
 #[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
 pub fn find(g: Graph, iset: Nodes, oset: Nodes, planes: Planes) -> Option<(GFlow, Layer)> {
+    let allowed = single_planes(&planes);
+    let (f, layer, _) = find_impl(&g, &iset, &oset, &allowed, &mut FindAux::default())?;
+    Some((f, layer))
+}
+
+/// Checks if a generalized flow exists, without constructing it.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - Equivalent to `find(g, iset, oset, planes).is_some()`, but only checks the consistency of each equation.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn exists(g: Graph, iset: Nodes, oset: Nodes, planes: Planes) -> bool {
     validate::check_graph(&g, &iset, &oset).unwrap();
-    let n = g.len();
-    let vset = (0..n).collect::<Nodes>();
-    let mut cset = Nodes::new();
-    // Need to use BTreeSet to get deterministic order
-    let mut ocset = vset.difference(&oset).copied().collect::<OrderedNodes>();
-    let mut omiset = oset.difference(&iset).copied().collect::<OrderedNodes>();
-    let mut f = GFlow::with_capacity(ocset.len());
-    let mut layer = vec![0_usize; n];
-    let mut nrows = ocset.len();
-    let mut ncols = omiset.len();
-    let mut neqs = ocset.len();
-    let mut work = vec![FixedBitSet::with_capacity(ncols + neqs); nrows];
-    for l in 1_usize.. {
-        cset.clear();
-        if ocset.is_empty() || omiset.is_empty() {
-            break;
+    exists_impl(&g, &iset, &oset, &single_planes(&planes))
+}
+
+/// Same as `find`, but minimizes the size of each correction set.
+///
+/// # Note
+///
+/// - The layer is the same as that of `find`, as only the choice among the solutions of each node changes.
+/// - Falls back to the solution of `find` for nodes with too many candidates, as in `GF2Solver::solve_min_weight`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_min(g: Graph, iset: Nodes, oset: Nodes, planes: Planes) -> Option<(GFlow, Layer)> {
+    let mut aux = FindAux {
+        min_weight: true,
+        ..Default::default()
+    };
+    let allowed = single_planes(&planes);
+    let (f, layer, _) = find_impl(&g, &iset, &oset, &allowed, &mut aux)?;
+    Some((f, layer))
+}
+
+/// Same as `find`, but returns the gflow as a CSR-like pair of integer arrays.
+///
+/// # Returns
+///
+/// `(indptr, indices, layer)`, where `f(i)` is `indices[indptr[i]..indptr[i + 1]]` in ascending order.
+///
+/// # Errors
+///
+/// If the `array` module fails to construct the result.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - Rows span all the nodes, with the output nodes left empty.
+/// - Both arrays are `array.array` of typecode `"Q"`, shared by `numpy.asarray` without copying.
+#[pyfunction]
+#[tracing::instrument(skip(py))]
+#[allow(clippy::needless_pass_by_value)]
+pub fn find_csr(
+    py: Python<'_>,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+) -> PyResult<Option<(PyObject, PyObject, Layer)>> {
+    let allowed = single_planes(&planes);
+    let Some((f, layer, _)) = find_impl(&g, &iset, &oset, &allowed, &mut FindAux::default()) else {
+        return Ok(None);
+    };
+    let mut indptr = Vec::with_capacity(g.len() + 1);
+    let mut indices = Vec::with_capacity(f.values().map(Nodes::len).sum());
+    indptr.push(0);
+    for u in 0..g.len() {
+        if let Some(fu) = f.get(&u) {
+            let start = indices.len();
+            indices.extend(fu);
+            indices[start..].sort_unstable();
+        }
+        indptr.push(indices.len());
+    }
+    let indptr = utils::u64_array(py, &indptr)?;
+    let indices = utils::u64_array(py, &indices)?;
+    Ok(Some((indptr.unbind(), indices.unbind(), layer)))
+}
+
+/// Tries to correct a single node against the already-corrected nodes.
+///
+/// # Arguments
+///
+/// - `g`: The adjacency list of the graph. Must be undirected and without self-loops.
+/// - `iset`: The set of initial nodes.
+/// - `oset_fixed`: The set of nodes already corrected, including the output nodes.
+/// - `u`: The node to correct. Must not be in `oset_fixed`.
+/// - `plane`: Measurement plane of `u`.
+///
+/// # Returns
+///
+/// The correction set of `u` if exists, otherwise `None`.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation or `u` is already corrected.
+///
+/// # Note
+///
+/// - Solves the same system as one step of `find`, so the result agrees with `find` in the first layer.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn correctable(
+    g: Graph,
+    iset: Nodes,
+    oset_fixed: Nodes,
+    u: usize,
+    plane: Plane,
+) -> Option<Nodes> {
+    validate::check_graph(&g, &iset, &oset_fixed).unwrap();
+    assert!(u < g.len(), "unknown node: {u}");
+    assert!(!oset_fixed.contains(&u), "already corrected: {u}");
+    let ocset = (0..g.len())
+        .filter(|v| !oset_fixed.contains(v))
+        .collect::<OrderedNodes>();
+    let omiset = oset_fixed
+        .difference(&iset)
+        .copied()
+        .collect::<OrderedNodes>();
+    let ncols = omiset.len();
+    let mut work = vec![FixedBitSet::new(); ocset.len()];
+    utils::zerofill(&mut work, ncols + 1);
+    init_work(&mut work, &g, &[(u, plane)], &ocset, &omiset);
+    let colnodes = omiset.iter().copied().collect::<Vec<_>>();
+    let mut solver = GF2Solver::attach(&mut work, 1);
+    let mut x = FixedBitSet::with_capacity(ncols);
+    if !solver.solve_in_place(&mut x, 0) {
+        return None;
+    }
+    let mut fu = x.ones().map(|c| colnodes[c]).collect::<Nodes>();
+    if let Plane::YZ | Plane::XZ = plane {
+        fu.insert(u);
+    }
+    Some(fu)
+}
+
+/// Builds the linear system `find` solves once `fixed` are corrected.
+///
+/// # Arguments
+///
+/// - `fixed`: Non-output nodes already corrected, i.e., those in the preceding layers.
+/// - Others: Same as `find`.
+///
+/// # Returns
+///
+/// Coefficient matrix and right-hand sides as row-major boolean matrices.
+///
+/// - Rows: Uncorrected nodes `V\(O | fixed)`, in ascending order.
+/// - Columns: Candidates of `f(u)` other than `u`, `(O | fixed)\I`, in ascending order.
+/// - Right-hand sides: One per uncorrected node `u`, in the same order as the rows.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation, `fixed` contains unknown nodes, or `planes` lacks any uncorrected node.
+///
+/// # Note
+///
+/// - Solution `x` of the right-hand side for `u` gives `f(u)`, plus `u` itself unless XY.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn layer_matrix(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+    fixed: Nodes,
+) -> (Vec<Vec<bool>>, Vec<Vec<bool>>) {
+    validate::check_graph(&g, &iset, &oset).unwrap();
+    assert!(fixed.iter().all(|&u| u < g.len()), "unknown node in fixed");
+    let rowset = (0..g.len())
+        .filter(|u| !oset.contains(u) && !fixed.contains(u))
+        .collect::<OrderedNodes>();
+    let omiset = oset
+        .union(&fixed)
+        .filter(|&u| !iset.contains(u))
+        .copied()
+        .collect::<OrderedNodes>();
+    let eqs = rowset.iter().map(|&u| (u, planes[&u])).collect::<Vec<_>>();
+    let ncols = omiset.len();
+    let mut work = vec![FixedBitSet::new(); rowset.len()];
+    utils::zerofill(&mut work, ncols + eqs.len());
+    init_work(&mut work, &g, &eqs, &rowset, &omiset);
+    let block = |row: &FixedBitSet, cols: Range<usize>| cols.map(|c| row[c]).collect();
+    let co = work.iter().map(|row| block(row, 0..ncols)).collect();
+    let rhs = work
+        .iter()
+        .map(|row| block(row, ncols..ncols + eqs.len()))
+        .collect();
+    (co, rhs)
+}
+
+/// Finds the maximally-delayed generalized flow, choosing the measurement plane of each node.
+///
+/// # Arguments
+///
+/// - `g`: The adjacency list of the graph. Must be undirected and without self-loops.
+/// - `iset`: The set of initial nodes.
+/// - `oset`: The set of output nodes.
+/// - `allowed`: Allowed measurement planes of each node in V\O, in the order of precedence.
+///
+/// # Returns
+///
+/// Gflow, layer, and the chosen plane of each node in V\O.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - Each node is corrected as early as one of its allowed planes permits.
+///   If several planes work in that layer, the first one in `allowed[u]` is chosen.
+/// - Nodes with no allowed planes are never corrected.
+/// - Equivalent to `find` if every node has exactly one allowed plane.
+/// - The result also has the minimum depth among all the choices of planes:
+///   allowing more planes never shrinks the set of nodes corrected by each layer.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_multiplane(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    allowed: AllowedPlanes,
+) -> Option<(GFlow, Layer, Planes)> {
+    find_impl(&g, &iset, &oset, &allowed, &mut FindAux::default())
+}
+
+/// Same as `find`, but with the graph given as a map from node to its neighbors.
+///
+/// # Arguments
+///
+/// - `adj`: The adjacency map of the graph. Keys need not be contiguous.
+/// - Others: Same as `find`, with the keys of `adj` as nodes.
+///
+/// # Returns
+///
+/// Gflow and layer, both keyed by the nodes of `adj`.
+///
+/// # Errors
+///
+/// If `adj` is not symmetric, other arguments contain unknown nodes, or some node in V\O lacks its plane.
+///
+/// # Note
+///
+/// - Nodes are relabeled as `0..adj.len()` in ascending order, and the results are mapped back.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn find_from_dict(
+    adj: hashbrown::HashMap<usize, Nodes>,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+) -> PyResult<Option<(GFlow, hashbrown::HashMap<usize, usize>)>> {
+    // perm: new -> old, inv: old -> new
+    let keys = adj.keys().copied().collect::<OrderedNodes>();
+    let perm = keys.iter().copied().collect::<Vec<_>>();
+    let inv = utils::indexmap::<hashbrown::HashMap<_, _>>(&keys);
+    let to_new = |u: &usize, what: &str| {
+        inv.get(u)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("unknown node in {what}: {u}"))
+    };
+    let check = || {
+        for (u, au) in &adj {
+            for v in au {
+                if !adj.get(v).is_some_and(|av| av.contains(u)) {
+                    anyhow::bail!("adj must be undirected: needs {v} -> {u}");
+                }
+            }
+        }
+        let g = perm
+            .iter()
+            .map(|u| adj[u].iter().map(|v| inv[v]).collect())
+            .collect::<Graph>();
+        let iset = iset
+            .iter()
+            .map(|u| to_new(u, "iset"))
+            .collect::<anyhow::Result<_>>()?;
+        let oset = oset
+            .iter()
+            .map(|u| to_new(u, "oset"))
+            .collect::<anyhow::Result<_>>()?;
+        let allowed = planes
+            .iter()
+            .map(|(u, &pu)| Ok((to_new(u, "planes")?, vec![pu])))
+            .collect::<anyhow::Result<AllowedPlanes>>()?;
+        validate::check_graph(&g, &iset, &oset)?;
+        if let Some(u) = (0..g.len()).find(|u| !oset.contains(u) && !allowed.contains_key(u)) {
+            anyhow::bail!("plane of {} not specified", perm[u]);
+        }
+        Ok((g, iset, oset, allowed))
+    };
+    let (g, iset, oset, allowed) = check().map_err(utils::to_pyerr)?;
+    let Some((f, layer, _)) = find_impl(&g, &iset, &oset, &allowed, &mut FindAux::default()) else {
+        return Ok(None);
+    };
+    let f = f
+        .iter()
+        .map(|(&i, fi)| (perm[i], fi.iter().map(|&j| perm[j]).collect()))
+        .collect::<GFlow>();
+    let layer = layer
+        .iter()
+        .enumerate()
+        .map(|(k, &l)| (perm[k], l))
+        .collect();
+    Ok(Some((f, layer)))
+}
+
+/// Enumerates the maximally-delayed generalized flows.
+///
+/// # Arguments
+///
+/// - `limit`: Maximum number of gflows to return.
+/// - Others: Same as `find`.
+///
+/// # Returns
+///
+/// Distinct gflows sharing the same maximally-delayed layer, the first one being the output of `find`.
+/// Empty if no gflow is found.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - The candidates of `f(u)` are the solution of the linear system of its layer, shifted by the kernel basis.
+/// - All the combinations are enumerated only if their number does not exceed `limit`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_all(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+    limit: usize,
+) -> Vec<(GFlow, Layer)> {
+    let mut aux = FindAux {
+        kernels: Some(Vec::new()),
+        ..Default::default()
+    };
+    let allowed = single_planes(&planes);
+    let Some((f, layer, _)) = find_impl(&g, &iset, &oset, &allowed, &mut aux) else {
+        return Vec::new();
+    };
+    let kernels = aux.kernels.expect("kernels collected here");
+    // Degrees of freedom as (node, basis vector)
+    let mut dofs = Vec::new();
+    for u in f.keys().copied().collect::<OrderedNodes>() {
+        dofs.extend(kernels[layer[u] - 1].iter().map(|k| (u, k)));
+    }
+    let total = u32::try_from(dofs.len())
+        .ok()
+        .and_then(|d| 1_usize.checked_shl(d))
+        .map_or(limit, |t| t.min(limit));
+    let mut ret = Vec::with_capacity(total);
+    for idx in 0..total {
+        let mut fi = f.clone();
+        for (b, &(u, k)) in dofs.iter().enumerate().take(usize::BITS as usize) {
+            if idx >> b & 1 == 1 {
+                let fu = fi.get_mut(&u).expect("u in the domain of f");
+                for &v in k {
+                    if !fu.remove(&v) {
+                        fu.insert(v);
+                    }
+                }
+            }
+        }
+        ret.push((fi, layer.clone()));
+    }
+    ret
+}
+
+/// Computes the canonical form of the maximally-delayed generalized flow.
+///
+/// # Arguments
+///
+/// - `f`: Gflow function compatible with the maximally-delayed layer.
+/// - Others: Same as `find`.
+///
+/// # Returns
+///
+/// Canonical gflow and the maximally-delayed layer.
+///
+/// # Errors
+///
+/// If the inputs are invalid, gflow does not exist, or `f` is not valid with the maximally-delayed layer.
+///
+/// # Note
+///
+/// - `f(i)` is replaced by the minimum valid correction set of `i`, regarding the node `j` as the bit `2^j`.
+/// - The result depends only on `g`, `iset`, `oset`, and `planes`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn canonicalize(
+    f: GFlow,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+) -> PyResult<(GFlow, Layer)> {
+    let run = || {
+        validate::check_graph(&g, &iset, &oset)?;
+        let mut aux = FindAux {
+            kernels: Some(Vec::new()),
+            ..Default::default()
+        };
+        let allowed = single_planes(&planes);
+        let (_, layer, _) = find_impl(&g, &iset, &oset, &allowed, &mut aux)
+            .ok_or_else(|| anyhow::anyhow!("gflow not found"))?;
+        verify_impl(&f, &layer, &g, &iset, &oset, &planes)?;
+        let n = g.len();
+        let kernels = aux.kernels.unwrap_or_default();
+        let to_bits = |nodes: &Nodes| {
+            let mut x = FixedBitSet::with_capacity(n);
+            x.extend(nodes.iter().copied());
+            x
+        };
+        let mut ret = GFlow::with_capacity(f.len());
+        for (&u, fu) in &f {
+            let basis = kernels[layer[u] - 1]
+                .iter()
+                .map(to_bits)
+                .collect::<Vec<_>>();
+            let mut x = to_bits(fu);
+            // Not a variable of the linear system
+            x.set(u, false);
+            utils::reduce_by_span(&mut x, &basis);
+            x.set(u, fu.contains(&u));
+            ret.insert(u, x.ones().collect());
+        }
+        Ok((ret, layer))
+    };
+    run().map_err(utils::to_pyerr)
+}
+
+/// Finds the maximally-delayed generalized flow with some nodes excluded from `Odd(f(i))`.
+///
+/// # Arguments
+///
+/// - `forbidden`: Nodes never allowed in `Odd(f(i))` for any `i` other than themselves.
+/// - Others: Same as `find`.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation or `forbidden` contains unknown nodes.
+///
+/// # Note
+///
+/// - Useful to keep the inputs free from Z corrections, in addition to X corrections excluded by definition.
+/// - Each forbidden node adds a row constrained to zero to the linear systems.
+/// - Equivalent to `find` if `forbidden` is empty.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_with_forbidden_odd(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+    forbidden: Nodes,
+) -> Option<(GFlow, Layer)> {
+    assert!(
+        forbidden.iter().all(|&u| u < g.len()),
+        "unknown node in forbidden"
+    );
+    let mut aux = FindAux {
+        forbidden,
+        ..Default::default()
+    };
+    let (f, layer, _) = find_impl(&g, &iset, &oset, &single_planes(&planes), &mut aux)?;
+    Some((f, layer))
+}
+
+/// Updates `planes` for the local complementation at `u`.
+///
+/// # Note
+///
+/// - `u` swaps XY and XZ, while its neighbors swap XZ and YZ.
+/// - Output nodes have no plane and are left as is.
+fn local_complement_planes(planes: &mut Planes, g: &Graph, u: usize) {
+    if let Some(pu) = planes.get_mut(&u) {
+        *pu = match *pu {
+            Plane::XY => Plane::XZ,
+            Plane::XZ => Plane::XY,
+            Plane::YZ => Plane::YZ,
+        };
+    }
+    for v in &g[u] {
+        if let Some(pv) = planes.get_mut(v) {
+            *pv = match *pv {
+                Plane::XY => Plane::XY,
+                Plane::XZ => Plane::YZ,
+                Plane::YZ => Plane::XZ,
+            };
+        }
+    }
+}
+
+/// Advances `seq` to the next sequence over `0..n` in lexicographic order.
+///
+/// Returns `false` if `seq` was the last one.
+fn next_sequence(seq: &mut [usize], n: usize) -> bool {
+    for k in seq.iter_mut().rev() {
+        *k += 1;
+        if *k < n {
+            return true;
+        }
+        *k = 0;
+    }
+    false
+}
+
+/// Upper bound of `max_ops` in `find_with_local_complements`.
+const MAX_LOCAL_COMPLEMENTS: usize = 3;
+
+/// Finds gflow after a bounded sequence of local complementations.
+///
+/// # Arguments
+///
+/// - `max_ops`: Maximum number of local complementations.
+/// - Others: Same as `find`.
+///
+/// # Returns
+///
+/// Sequence of the nodes complemented in order, and gflow and layer of the resulting graph.
+///
+/// # Errors
+///
+/// If `max_ops` exceeds `MAX_LOCAL_COMPLEMENTS`.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - `planes` follow the local Cliffords induced by each complementation.
+/// - Shorter sequences are tried first, so the empty sequence is returned whenever `find` succeeds.
+/// - Complementing the same node twice in a row is skipped as it is the identity.
+/// - Tries up to `n^max_ops` sequences, hence the bound on `max_ops`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn find_with_local_complements(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: Planes,
+    max_ops: usize,
+) -> PyResult<Option<(Vec<usize>, GFlow, Layer)>> {
+    validate::check_graph(&g, &iset, &oset).unwrap();
+    if max_ops > MAX_LOCAL_COMPLEMENTS {
+        let err = anyhow::anyhow!("max_ops too large: {max_ops} > {MAX_LOCAL_COMPLEMENTS}");
+        return Err(utils::to_pyerr(err));
+    }
+    let n = g.len();
+    for d in 0..=max_ops {
+        let mut seq = vec![0; d];
+        loop {
+            if seq.windows(2).all(|w| w[0] != w[1]) {
+                let mut gc = g.clone();
+                let mut pc = planes.clone();
+                for &u in &seq {
+                    local_complement_planes(&mut pc, &gc, u);
+                    utils::local_complement(&mut gc, u);
+                }
+                let allowed = single_planes(&pc);
+                if let Some((f, layer, _)) =
+                    find_impl(&gc, &iset, &oset, &allowed, &mut FindAux::default())
+                {
+                    tracing::debug!("local complementations: {seq:?}");
+                    return Ok(Some((seq, f, layer)));
+                }
+            }
+            if !next_sequence(&mut seq, n) {
+                break;
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Converts `planes` into the single-element `AllowedPlanes`.
+fn single_planes(planes: &Planes) -> AllowedPlanes {
+    planes.iter().map(|(&u, &pu)| (u, vec![pu])).collect()
+}
+
+/// Finds a node that can never be corrected, whatever the other nodes do.
+///
+/// # Note
+///
+/// - Only XY requires `u` in `Odd(f(u))` with `u` not in `f(u)`, impossible if all the neighbors of `u` are inputs.
+/// - Necessary condition only: `None` does not imply that gflow exists.
+fn find_hopeless(
+    g: &Graph,
+    iset: &Nodes,
+    ocset: &OrderedNodes,
+    allowed: &AllowedPlanes,
+) -> Option<usize> {
+    ocset
+        .iter()
+        .copied()
+        .find(|u| allowed[u].iter().all(|&pu| pu == Plane::XY) && g[*u].is_subset(iset))
+}
+
+/// Checks if the uncorrected nodes can never be corrected, given the rank of the current layer.
+///
+/// # Arguments
+///
+/// - `ocset`: Uncorrected nodes at the top of the layer.
+/// - `rank`: Rank of the coefficient matrix of the layer, with the columns of the corrected non-input nodes.
+/// - Others: Same as `find_impl`.
+///
+/// # Note
+///
+/// - Let `R` be the nodes in `ocset` allowed only in XY, `C` the corrected non-input nodes, and `X = ocset - iset`.
+///   Every `f(u)` for `u` in `R` is in `C | X`, and `Odd(f(u)) & R` is `u` plus some nodes corrected before `u`.
+///   Thus `Gamma[R, C | X]` needs full row rank, which is impossible if `|R| > rank + |X|`.
+/// - Necessary condition only, evaluated at no extra cost as `rank` is computed anyway.
+/// - The bound may first fail after some layers, as `rank` grows slower than `|R| - |X|` shrinks.
+fn rank_bound_violated(
+    iset: &Nodes,
+    ocset: &OrderedNodes,
+    allowed: &AllowedPlanes,
+    rank: usize,
+) -> bool {
+    let nxy = ocset
+        .iter()
+        .filter(|&u| allowed[u].iter().all(|&pu| pu == Plane::XY))
+        .count();
+    let nx = ocset.iter().filter(|&u| !iset.contains(u)).count();
+    nxy > rank + nx
+}
+
+/// Auxiliary inputs and outputs of `find_impl`.
+#[derive(Debug, Default)]
+struct FindAux {
+    /// Nodes never allowed in `Odd(f(i))` other than `i`.
+    forbidden: Nodes,
+    /// Rank of the linear system solved for each corrected node.
+    ranks: hashbrown::HashMap<usize, usize>,
+    /// Number of free variables of the linear system solved for each corrected node.
+    nullities: hashbrown::HashMap<usize, usize>,
+    /// Kernel basis of the linear system solved in each layer, collected only if `Some`.
+    kernels: Option<Vec<Vec<Nodes>>>,
+    /// Minimize the size of each correction set if `true`.
+    min_weight: bool,
+    /// Solver statistics.
+    stats: FindStats,
+}
+
+impl FindAux {
+    /// Records the statistics and optionally the kernel of the system solved in a layer.
+    fn record(&mut self, solver: &mut GF2Solver, colnodes: &[usize]) {
+        self.stats.record_solver(solver);
+        if let Some(kernels) = &mut self.kernels {
+            let basis = solver.kernel_basis();
+            kernels.push(
+                basis
+                    .iter()
+                    .map(|v| v.ones().map(|c| colnodes[c]).collect())
+                    .collect(),
+            );
+        }
+    }
+}
+
+/// Implementation of `find` and `find_multiplane` that also fills `aux`.
+#[allow(clippy::too_many_lines)]
+fn find_impl(
+    g: &Graph,
+    iset: &Nodes,
+    oset: &Nodes,
+    allowed: &AllowedPlanes,
+    aux: &mut FindAux,
+) -> Option<(GFlow, Layer, Planes)> {
+    validate::check_graph(g, iset, oset).unwrap();
+    let n = g.len();
+    let mut cset = Nodes::new();
+    // Need to use BTreeSet to get deterministic order
+    let mut ocset = (0..n)
+        .filter(|u| !oset.contains(u))
+        .collect::<OrderedNodes>();
+    if let Some(u) = find_hopeless(g, iset, &ocset, allowed) {
+        tracing::debug!("gflow not found: {u} never correctable");
+        return None;
+    }
+    let mut omiset = oset.difference(iset).copied().collect::<OrderedNodes>();
+    let mut f = GFlow::with_capacity(ocset.len());
+    let mut layer = vec![0_usize; n];
+    let mut planes = Planes::with_capacity(ocset.len());
+    let mut eqs = Vec::new();
+    let mut rowset = OrderedNodes::new();
+    let mut work = vec![FixedBitSet::new(); ocset.len() + aux.forbidden.len()];
+    let mut x = FixedBitSet::new();
+    for l in 1_usize.. {
+        cset.clear();
+        if ocset.is_empty() || omiset.is_empty() {
+            break;
+        }
+        tracing::debug!("=====layer {l}=====");
+        eqs.clear();
+        eqs.extend(
+            ocset
+                .iter()
+                .flat_map(|&u| allowed[&u].iter().map(move |&pu| (u, pu))),
+        );
+        if eqs.is_empty() {
+            break;
+        }
+        // Uncorrected nodes and forbidden ones
+        rowset.clone_from(&ocset);
+        rowset.extend(&aux.forbidden);
+        // Decrease over time
+        let nrows = rowset.len();
+        let ncols = omiset.len();
+        let neqs = eqs.len();
+        debug_assert!(work.len() >= nrows);
+        work.truncate(nrows);
+        utils::zerofill(&mut work, ncols + neqs);
+        utils::zerofill(slice::from_mut(&mut x), ncols);
+        tracing::debug!("rowset: {rowset:?}");
+        tracing::debug!("colset: {omiset:?}");
+        tracing::debug!("eqset : {eqs:?}");
+        init_work(&mut work, g, &eqs, &rowset, &omiset);
+        // Column index to node
+        let colnodes = omiset.iter().copied().collect::<Vec<_>>();
+        let mut solver = GF2Solver::attach(&mut work, neqs);
+        tracing::debug!("{solver:?}");
+        // Shared by all the nodes in this layer
+        let rank = solver.rank();
+        aux.record(&mut solver, &colnodes);
+        if rank_bound_violated(iset, &ocset, allowed, rank) {
+            tracing::debug!("gflow not found: rank bound violated at layer {l}");
+            return None;
+        }
+        for (ieq, &(u, pu)) in eqs.iter().enumerate() {
+            if cset.contains(&u) {
+                // Already corrected by a preceding plane
+                continue;
+            }
+            let found = if aux.min_weight {
+                solver.solve_min_weight(&mut x, ieq)
+            } else {
+                solver.solve_in_place(&mut x, ieq)
+            };
+            if !found {
+                tracing::debug!("solution not found: {u} ({pu:?})");
+                continue;
+            }
+            cset.insert(u);
+            // Decode solution
+            let mut fu = x.ones().map(|c| colnodes[c]).collect::<Nodes>();
+            if let Plane::YZ | Plane::XZ = pu {
+                // Include u
+                fu.insert(u);
+            }
+            tracing::debug!("f({u}) = {fu:?}");
+            f.insert(u, fu);
+            tracing::debug!("layer({u}) = {l}");
+            layer[u] = l;
+            tracing::debug!("plane({u}) = {pu:?}");
+            planes.insert(u, pu);
+            aux.ranks.insert(u, rank);
+            aux.nullities.insert(u, ncols - rank);
+        }
+        if cset.is_empty() {
+            break;
+        }
+        ocset.difference_with(&cset);
+        omiset.extend(cset.difference(iset));
+    }
+    if ocset.is_empty() {
+        tracing::debug!("gflow found");
+        tracing::debug!("gflow: {f:?}");
+        tracing::debug!("layer: {layer:?}");
+        // TODO: Uncomment once ready
+        // if cfg!(debug_assertions) {
+        let sets = FlowSets::new(n, iset, oset);
+        let f_flatiter = f
+            .iter()
+            .flat_map(|(i, fi)| Iterator::zip(iter::repeat(i), fi.iter()));
+        validate::check_domain(f_flatiter, &sets).unwrap();
+        validate::check_initial(&layer, oset, true).unwrap();
+        check_definition(&f, &layer, g, &planes).unwrap();
+        // }
+        Some((f, layer, planes))
+    } else {
+        tracing::debug!("gflow not found");
+        None
+    }
+}
+
+/// Implementation of `exists`, running the same layered search as `find_impl`.
+fn exists_impl(g: &Graph, iset: &Nodes, oset: &Nodes, allowed: &AllowedPlanes) -> bool {
+    let n = g.len();
+    let mut ocset = (0..n)
+        .filter(|u| !oset.contains(u))
+        .collect::<OrderedNodes>();
+    if find_hopeless(g, iset, &ocset, allowed).is_some() {
+        return false;
+    }
+    let mut omiset = oset.difference(iset).copied().collect::<OrderedNodes>();
+    let mut cset = Nodes::new();
+    let mut eqs = Vec::new();
+    let mut work = vec![FixedBitSet::new(); ocset.len()];
+    while !ocset.is_empty() {
+        eqs.clear();
+        eqs.extend(
+            ocset
+                .iter()
+                .flat_map(|&u| allowed[&u].iter().map(move |&pu| (u, pu))),
+        );
+        if omiset.is_empty() || eqs.is_empty() {
+            return false;
+        }
+        let ncols = omiset.len();
+        let neqs = eqs.len();
+        work.truncate(ocset.len());
+        utils::zerofill(&mut work, ncols + neqs);
+        init_work(&mut work, g, &eqs, &ocset, &omiset);
+        let mut solver = GF2Solver::attach(&mut work, neqs);
+        if rank_bound_violated(iset, &ocset, allowed, solver.rank()) {
+            return false;
+        }
+        cset.clear();
+        for (ieq, &(u, _)) in eqs.iter().enumerate() {
+            if !cset.contains(&u) && solver.is_solvable(ieq) {
+                cset.insert(u);
+            }
+        }
+        if cset.is_empty() {
+            return false;
+        }
+        ocset.difference_with(&cset);
+        omiset.extend(cset.difference(iset));
+    }
+    true
+}
+
+/// Finds the maximally-delayed generalized flow and bundles it with the derived data.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Note
+///
+/// - `depth` is the number of layers.
+/// - `order` contains `(i, j)` for each `j` in `f(i)` or `Odd(f(i))` other than `i`.
+/// - `rank` is the rank of the linear system solved in the layer of each node.
+/// - `nullity` is the number of free variables of the same system, zero if `f(i)` is unique.
+/// - `stats` reports the fill-in and the operation counts of the linear systems.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_verbose(g: Graph, iset: Nodes, oset: Nodes, planes: Planes) -> Option<FlowReport> {
+    let mut aux = FindAux::default();
+    let (f, layer, _) = find_impl(&g, &iset, &oset, &single_planes(&planes), &mut aux)?;
+    let depth = utils::depth(&layer);
+    let odd = utils::odd_neighbors_map(&f, &g);
+    let order = utils::order_edges(&f, &layer, &g);
+    Some(FlowReport {
+        f,
+        layer,
+        depth,
+        odd,
+        order,
+        rank: aux.ranks,
+        nullity: aux.nullities,
+        stats: aux.stats,
+    })
+}
+
+/// Lists the nodes whose correction set is uniquely determined.
+///
+/// # Arguments
+///
+/// - `report`: Output of `find_verbose`.
+///
+/// # Note
+///
+/// - The correction sets of the other nodes can be reshaped without changing the layer.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn forced_nodes(report: FlowReport) -> Nodes {
+    report
+        .nullity
+        .iter()
+        .filter_map(|(&u, &k)| (k == 0).then_some(u))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use pyo3::{exceptions::PyZeroDivisionError, types::PyList};
+    use test_log;
+
+    use super::*;
+    use crate::internal::test_utils::{self, TestCase};
+
+    #[test_log::test]
+    fn test_find_case0() {
+        let TestCase { g, iset, oset } = test_utils::CASE0.clone();
+        let planes = measurements! {};
+        let flen = g.len() - oset.len();
+        let (f, layer) = find(g, iset, oset, planes).unwrap();
+        assert_eq!(f.len(), flen);
+        assert_eq!(layer, vec![0, 0]);
+    }
+
+    #[test_log::test]
+    fn test_find_case1() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
+        };
+        let flen = g.len() - oset.len();
+        let (f, layer) = find(g, iset, oset, planes).unwrap();
+        assert_eq!(f.len(), flen);
+        assert_eq!(f[&0], Nodes::from([1]));
+        assert_eq!(f[&1], Nodes::from([2]));
+        assert_eq!(f[&2], Nodes::from([3]));
+        assert_eq!(f[&3], Nodes::from([4]));
+        assert_eq!(layer, vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test_log::test]
+    fn test_find_case2() {
+        let TestCase { g, iset, oset } = test_utils::CASE2.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
+        };
+        let flen = g.len() - oset.len();
+        let (f, layer) = find(g, iset, oset, planes).unwrap();
+        assert_eq!(f.len(), flen);
+        assert_eq!(f[&0], Nodes::from([2]));
+        assert_eq!(f[&1], Nodes::from([3]));
+        assert_eq!(f[&2], Nodes::from([4]));
+        assert_eq!(f[&3], Nodes::from([5]));
+        assert_eq!(layer, vec![2, 2, 1, 1, 0, 0]);
+    }
+
+    #[test_log::test]
+    fn test_find_case3() {
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY
+        };
+        let flen = g.len() - oset.len();
+        let (f, layer) = find(g, iset, oset, planes).unwrap();
+        assert_eq!(f.len(), flen);
+        assert_eq!(f[&0], Nodes::from([4, 5]));
+        assert_eq!(f[&1], Nodes::from([3, 4, 5]));
+        assert_eq!(f[&2], Nodes::from([3, 5]));
+        assert_eq!(layer, vec![1, 1, 1, 0, 0, 0]);
+    }
+
+    #[test_log::test]
+    fn test_find_case4() {
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XZ,
+            3: Plane::YZ
+        };
+        let flen = g.len() - oset.len();
+        let (f, layer) = find(g, iset, oset, planes).unwrap();
+        assert_eq!(f.len(), flen);
+        assert_eq!(f[&0], Nodes::from([2]));
+        assert_eq!(f[&1], Nodes::from([5]));
+        assert_eq!(f[&2], Nodes::from([2, 4]));
+        assert_eq!(f[&3], Nodes::from([3]));
+        assert_eq!(layer, vec![2, 2, 1, 1, 0, 0]);
+    }
+
+    #[test_log::test]
+    fn test_find_case5() {
+        let TestCase { g, iset, oset } = test_utils::CASE5.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY
+        };
+        assert!(find(g, iset, oset, planes).is_none());
+    }
+
+    #[test_log::test]
+    fn test_find_verbose_case4() {
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XZ,
+            3: Plane::YZ
+        };
+        let report = find_verbose(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let (f, layer) = find(g.clone(), iset, oset, planes).unwrap();
+        assert_eq!(report.f, f);
+        assert_eq!(report.layer, layer);
+        assert_eq!(report.depth, utils::depth(&layer));
+        assert_eq!(report.depth, 3);
+        assert_eq!(report.odd.len(), f.len());
+        for (i, fi) in &f {
+            assert_eq!(report.odd[i], utils::odd_neighbors(&g, fi));
+        }
+        assert_eq!(report.order, utils::order_edges(&f, &layer, &g));
+        assert_eq!(report.rank.len(), f.len());
+        assert!(report.rank.values().all(|&r| r == 2));
+        assert_eq!(report.nullity.len(), f.len());
+        assert!(report.stats.max_row_weight > 0);
+        assert!(report.stats.total_ones >= 4);
+        assert_eq!(
+            report.order,
+            vec![
+                (0, 2),
+                (0, 4),
+                (0, 5),
+                (1, 2),
+                (1, 3),
+                (1, 5),
+                (2, 4),
+                (2, 5),
+                (3, 5)
+            ]
+        );
+    }
+
+    #[test_log::test]
+    fn test_find_with_local_complements() {
+        // 1 - 0 - 2
+        let g = vec![Nodes::from([1, 2]), Nodes::from([0]), Nodes::from([0])];
+        let iset = Nodes::from([0]);
+        let oset = Nodes::from([2]);
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY
+        };
+        // 1 only neighbors the input 0
+        assert!(find(g.clone(), iset.clone(), oset.clone(), planes.clone()).is_none());
+        let ret =
+            find_with_local_complements(g.clone(), iset.clone(), oset.clone(), planes.clone(), 0);
+        assert!(ret.unwrap().is_none());
+        let ret =
+            find_with_local_complements(g.clone(), iset.clone(), oset.clone(), planes.clone(), 2);
+        let (seq, f, layer) = ret.unwrap().unwrap();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|_| {
+            let ret = find_with_local_complements(
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                planes.clone(),
+                MAX_LOCAL_COMPLEMENTS + 1,
+            );
+            let err = ret.unwrap_err();
+            assert!(err.to_string().contains("max_ops too large"));
+        });
+        assert_eq!(seq, vec![0]);
+        let mut gc = g;
+        let mut pc = planes;
+        local_complement_planes(&mut pc, &gc, 0);
+        utils::local_complement(&mut gc, 0);
+        assert_eq!(gc[1], Nodes::from([0, 2]));
+        assert_eq!(pc[&0], Plane::XZ);
+        assert_eq!(pc[&1], Plane::XY);
+        verify(f, layer, gc, iset, oset, pc, false, None).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_is_valid_order_case1() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let (f, layer) = crate::flow::find(g.clone(), iset, oset).unwrap();
+        assert_eq!(layer, vec![4, 3, 2, 1, 0]);
+        assert!(is_valid_order(AnyFlow::Flow(f.clone()), layer, g.clone()));
+        // Not maximally-delayed but still causal
+        let eager = vec![8, 6, 5, 2, 0];
+        assert!(is_valid_order(
+            AnyFlow::Flow(f.clone()),
+            eager.clone(),
+            g.clone()
+        ));
+        let gf = f
+            .iter()
+            .map(|(&i, &fi)| (i, Nodes::from([fi])))
+            .collect::<GFlow>();
+        assert!(is_valid_order(AnyFlow::GFlow(gf), eager, g.clone()));
+        assert!(!is_valid_order(AnyFlow::Flow(f), vec![3, 4, 2, 1, 0], g));
+    }
+
+    #[test_log::test]
+    fn test_find_csr_case4() {
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XZ,
+            3: Plane::YZ
+        };
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let (indptr, indices, layer_csr) = find_csr(py, g.clone(), iset, oset, planes)
+                .unwrap()
+                .unwrap();
+            let tolist = |a: PyObject| {
+                a.call_method0(py, "tolist")
+                    .unwrap()
+                    .extract::<Vec<usize>>(py)
+                    .unwrap()
+            };
+            let indptr = tolist(indptr);
+            let indices = tolist(indices);
+            assert_eq!(layer_csr, layer);
+            assert_eq!(indptr.len(), g.len() + 1);
+            for (u, w) in indptr.windows(2).enumerate() {
+                let fu = &indices[w[0]..w[1]];
+                assert!(fu.windows(2).all(|p| p[0] < p[1]));
+                let fu = fu.iter().copied().collect::<Nodes>();
+                assert_eq!(f.get(&u).cloned().unwrap_or_default(), fu);
+            }
+        });
+    }
+
+    #[test_log::test]
+    fn test_correction_volume_case3() {
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY
+        };
+        let (f, _) = find(g.clone(), iset, oset, planes).unwrap();
+        // Odd(f(i)) = {i} for all i: (2 + 1) + (3 + 1) + (2 + 1)
+        assert_eq!(correction_volume(AnyFlow::GFlow(f), g), 10);
+    }
+
+    #[test_log::test]
+    fn test_correction_volume_flow() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let (f, _) = crate::flow::find(g.clone(), iset, oset).unwrap();
+        let gf = f
+            .iter()
+            .map(|(&i, &fi)| (i, Nodes::from([fi])))
+            .collect::<GFlow>();
+        // |N(f(i))| = 2 except for f(3) = 4
+        assert_eq!(correction_volume(AnyFlow::Flow(f), g.clone()), 11);
+        assert_eq!(correction_volume(AnyFlow::GFlow(gf), g), 11);
+    }
+
+    #[test_log::test]
+    fn test_layer_matrix_case4() {
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XZ,
+            3: Plane::YZ
+        };
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        for l in 1..utils::depth(&layer) {
+            let fixed = (0..g.len())
+                .filter(|u| !oset.contains(u) && layer[*u] < l)
+                .collect::<Nodes>();
+            let (co, rhs) = layer_matrix(
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                planes.clone(),
+                fixed.clone(),
+            );
+            let rows = (0..g.len())
+                .filter(|u| !oset.contains(u) && !fixed.contains(u))
+                .collect::<Vec<_>>();
+            let cols = oset
+                .union(&fixed)
+                .filter(|&u| !iset.contains(u))
+                .copied()
+                .sorted()
+                .collect::<Vec<_>>();
+            assert_eq!((co.len(), rhs.len()), (rows.len(), rows.len()));
+            let mut work = co
+                .iter()
+                .zip(&rhs)
+                .map(|(a, b)| {
+                    let mut row = FixedBitSet::with_capacity(a.len() + b.len());
+                    row.extend(a.iter().chain(b).positions(|&x| x));
+                    row
+                })
+                .collect::<Vec<_>>();
+            let mut solver = GF2Solver::attach(&mut work, rows.len());
+            let mut x = FixedBitSet::with_capacity(cols.len());
+            for (ieq, &u) in rows.iter().enumerate() {
+                let solvable = solver.solve_in_place(&mut x, ieq);
+                assert_eq!(solvable, layer[u] == l, "node {u} at layer {l}");
+                if !solvable {
+                    continue;
+                }
+                let mut fu = x.ones().map(|c| cols[c]).collect::<Nodes>();
+                if planes[&u] != Plane::XY {
+                    fu.insert(u);
+                }
+                assert_eq!(fu, f[&u]);
+            }
+        }
+    }
+
+    #[test_log::test]
+    fn test_forced_nodes_case3() {
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY
+        };
+        let report = find_verbose(g, iset, oset, planes).unwrap();
+        // Unique gflow
+        assert_eq!(forced_nodes(report), Nodes::from([0, 1, 2]));
+    }
+
+    #[test_log::test]
+    fn test_forced_nodes_case1() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
+        };
+        let report = find_verbose(g, iset, oset, planes).unwrap();
+        // 4 is free to join f(i) for i < 3
+        assert_eq!(forced_nodes(report), Nodes::from([3]));
+    }
+
+    #[test_log::test]
+    fn test_check_node_correction_ok() {
+        let TestCase { g, .. } = test_utils::CASE4.clone();
+        let layer = vec![2, 2, 1, 1, 0, 0];
+        let cases = [
+            (0, Nodes::from([2]), Plane::XY),
+            (1, Nodes::from([5]), Plane::XY),
+            (2, Nodes::from([2, 4]), Plane::XZ),
+            (3, Nodes::from([3]), Plane::YZ),
+        ];
+        for (i, ci, plane) in cases {
+            check_node_correction(i, ci, g.clone(), layer.clone(), plane).unwrap();
         }
-        tracing::debug!("=====layer {l}=====");
-        // Decrease over time
-        nrows = ocset.len();
-        ncols = omiset.len();
-        neqs = ocset.len();
-        debug_assert!(work.len() >= nrows);
-        work.truncate(nrows);
-        // Decrease over time
-        debug_assert!(work[0].len() >= ncols + neqs);
-        utils::zerofill(&mut work, ncols + neqs);
-        tracing::debug!("rowset: {ocset:?}");
-        tracing::debug!("colset: {omiset:?}");
-        tracing::debug!("eqset : {ocset:?}");
-        tracing::debug!(
-            "planes: {:?}",
-            ocset.iter().map(|&u| planes[&u]).collect::<Vec<_>>()
-        );
-        init_work(&mut work, &g, &planes, &ocset, &omiset);
-        let mut solver = GF2Solver::attach(&mut work, neqs);
-        let mut x = FixedBitSet::with_capacity(ncols);
-        tracing::debug!("{solver:?}");
-        for (ieq, &u) in ocset.iter().enumerate() {
-            if !solver.solve_in_place(&mut x, ieq) {
-                tracing::debug!("solution not found: {u}");
-                continue;
+    }
+
+    #[test_log::test]
+    fn test_check_node_correction_ng() {
+        let TestCase { g, .. } = test_utils::CASE4.clone();
+        let layer = vec![2, 2, 1, 1, 0, 0];
+        let cases = [
+            // Layer check on f(i)
+            (2, Nodes::from([0]), Plane::XY),
+            // Layer check on Odd(f(i))
+            (3, Nodes::from([5]), Plane::XY),
+            // Plane checks
+            (0, Nodes::from([2, 4]), Plane::XY),
+            (0, Nodes::from([2]), Plane::YZ),
+            (3, Nodes::from([3]), Plane::XZ),
+            // Out of range
+            (6, Nodes::from([3]), Plane::XY),
+            (3, Nodes::from([6]), Plane::XY),
+        ];
+        for (i, ci, plane) in cases {
+            assert!(check_node_correction(i, ci, g.clone(), layer.clone(), plane).is_err());
+        }
+        assert!(check_node_correction(3, Nodes::from([3]), g, vec![0; 5], Plane::YZ).is_err());
+    }
+
+    #[test_log::test]
+    fn test_find_multiplane_single() {
+        let cases = [
+            (
+                test_utils::CASE3.clone(),
+                measurements! {
+                    0: Plane::XY,
+                    1: Plane::XY,
+                    2: Plane::XY
+                },
+            ),
+            (
+                test_utils::CASE4.clone(),
+                measurements! {
+                    0: Plane::XY,
+                    1: Plane::XY,
+                    2: Plane::XZ,
+                    3: Plane::YZ
+                },
+            ),
+            (
+                test_utils::CASE5.clone(),
+                measurements! {
+                    0: Plane::XY,
+                    1: Plane::XY
+                },
+            ),
+        ];
+        for (TestCase { g, iset, oset }, planes) in cases {
+            let allowed = single_planes(&planes);
+            let expected = find(g.clone(), iset.clone(), oset.clone(), planes.clone());
+            let actual = find_multiplane(g, iset, oset, allowed);
+            match (actual, expected) {
+                (Some((f, layer, chosen)), Some((f_, layer_))) => {
+                    assert_eq!(f, f_);
+                    assert_eq!(layer, layer_);
+                    assert_eq!(chosen, planes);
+                }
+                (None, None) => {}
+                _ => panic!("find_multiplane and find disagree"),
             }
-            cset.insert(u);
-            // Decode solution
-            let mut fu = omiset
+        }
+    }
+
+    #[test_log::test]
+    fn test_find_multiplane_precedence() {
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        for ps in [[Plane::YZ, Plane::XZ], [Plane::XZ, Plane::YZ]] {
+            let allowed = (0..4).map(|u| (u, ps.to_vec())).collect();
+            let (f, layer, planes) =
+                find_multiplane(g.clone(), iset.clone(), oset.clone(), allowed).unwrap();
+            assert_eq!(f.len(), 4);
+            assert_eq!(layer, vec![2, 2, 1, 1, 0, 0]);
+            // Both planes work for 0 and 1
+            assert_eq!(planes[&0], ps[0]);
+            assert_eq!(planes[&1], ps[0]);
+            // Only XZ works for 2
+            assert_eq!(planes[&2], Plane::XZ);
+            assert_eq!(planes[&3], Plane::YZ);
+        }
+    }
+
+    #[test_log::test]
+    fn test_find_multiplane_case5() {
+        let TestCase { g, iset, oset } = test_utils::CASE5.clone();
+        let allowed = (0..2).map(|u| (u, vec![Plane::XY, Plane::YZ])).collect();
+        let (f, layer, planes) = find_multiplane(g, iset, oset, allowed).unwrap();
+        assert_eq!(f[&0], Nodes::from([0]));
+        assert_eq!(f[&1], Nodes::from([1]));
+        assert_eq!(layer, vec![1, 1, 0, 0]);
+        assert_eq!(planes, measurements! { 0: Plane::YZ, 1: Plane::YZ });
+    }
+
+    #[test_log::test]
+    fn test_find_multiplane_depth() {
+        let all = vec![Plane::XY, Plane::YZ, Plane::XZ];
+        for TestCase { g, iset, oset } in [
+            test_utils::CASE1.clone(),
+            test_utils::CASE3.clone(),
+            test_utils::CASE4.clone(),
+            test_utils::CASE5.clone(),
+            test_utils::CASE8.clone(),
+        ] {
+            let ocset = (0..g.len()).filter(|u| !oset.contains(u)).collect_vec();
+            let multi = ocset
                 .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| if x[i] { Some(v) } else { None })
-                .collect::<Nodes>();
-            if let Plane::YZ | Plane::XZ = planes[&u] {
-                // Include u
-                fu.insert(u);
+                .map(|&u| (u, all.clone()))
+                .collect::<AllowedPlanes>();
+            let (f, layer, planes) =
+                find_multiplane(g.clone(), iset.clone(), oset.clone(), multi).unwrap();
+            check_definition(&f, &layer, &g, &planes).unwrap();
+            // No single choice of planes is shallower
+            for choice in ocset.iter().map(|_| all.iter()).multi_cartesian_product() {
+                let single = ocset
+                    .iter()
+                    .zip(choice)
+                    .map(|(&u, &pu)| (u, vec![pu]))
+                    .collect::<AllowedPlanes>();
+                let (g, iset, oset) = (g.clone(), iset.clone(), oset.clone());
+                if let Some((_, other, _)) = find_multiplane(g, iset, oset, single) {
+                    assert!(utils::depth(&layer) <= utils::depth(&other));
+                }
             }
-            tracing::debug!("f({u}) = {fu:?}");
-            f.insert(u, fu);
-            tracing::debug!("layer({u}) = {l}");
-            layer[u] = l;
-        }
-        if cset.is_empty() {
-            break;
         }
-        ocset.difference_with(&cset);
-        omiset.extend(cset.difference(&iset));
     }
-    if ocset.is_empty() {
-        tracing::debug!("gflow found");
-        tracing::debug!("gflow: {f:?}");
-        tracing::debug!("layer: {layer:?}");
-        // TODO: Uncomment once ready
-        // if cfg!(debug_assertions) {
-        let f_flatiter = f
-            .iter()
-            .flat_map(|(i, fi)| Iterator::zip(iter::repeat(i), fi.iter()));
-        validate::check_domain(f_flatiter, &vset, &iset, &oset).unwrap();
-        validate::check_initial(&layer, &oset, true).unwrap();
-        check_definition(&f, &layer, &g, &planes).unwrap();
-        // }
-        Some((f, layer))
-    } else {
-        tracing::debug!("gflow not found");
-        None
+
+    #[test_log::test]
+    fn test_find_case6() {
+        let TestCase { g, iset, oset } = test_utils::CASE6.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
+        };
+        assert!(find(g, iset, oset, planes).is_none());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use test_log;
+    #[test_log::test]
+    fn test_find_case7() {
+        let TestCase { g, iset, oset } = test_utils::CASE7.clone();
+        let planes = measurements! {
+            0: Plane::YZ,
+            1: Plane::XZ,
+            2: Plane::XY,
+            3: Plane::YZ
+        };
+        assert!(find(g, iset, oset, planes).is_none());
+    }
 
-    use super::*;
-    use crate::internal::test_utils::{self, TestCase};
+    #[test_log::test]
+    fn test_find_case8() {
+        let TestCase { g, iset, oset } = test_utils::CASE8.clone();
+        let planes = measurements! {
+            0: Plane::YZ,
+            1: Plane::XZ,
+            2: Plane::XY
+        };
+        assert!(find(g, iset, oset, planes).is_none());
+    }
 
     #[test_log::test]
-    fn test_find_case0() {
-        let TestCase { g, iset, oset } = test_utils::CASE0.clone();
-        let planes = measurements! {};
-        let flen = g.len() - oset.len();
-        let (f, layer) = find(g, iset, oset, planes).unwrap();
-        assert_eq!(f.len(), flen);
-        assert_eq!(layer, vec![0, 0]);
+    fn test_verify_maximal() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
+        };
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let args = (g, iset, oset, planes);
+        let verify_strict =
+            |f, layer, g, iset, oset, planes| verify(f, layer, g, iset, oset, planes, false, None);
+        let verify_with =
+            |layer: &Layer,
+             check: fn(GFlow, Layer, Graph, Nodes, Nodes, Planes) -> PyResult<()>| {
+                let (g, iset, oset, planes) = args.clone();
+                check(f.clone(), layer.clone(), g, iset, oset, planes)
+            };
+        verify_with(&layer, verify_strict).unwrap();
+        verify_with(&layer, verify_maximal).unwrap();
+        // Still valid, but too eager
+        let layer_eager = vec![5, 4, 3, 2, 0];
+        verify_with(&layer_eager, verify_strict).unwrap();
+        assert!(verify_with(&layer_eager, verify_maximal).is_err());
+        // Invalid
+        let layer_invalid = vec![4, 3, 2, 2, 0];
+        assert!(verify_with(&layer_invalid, verify_strict).is_err());
+        assert!(verify_with(&layer_invalid, verify_maximal).is_err());
     }
 
     #[test_log::test]
-    fn test_find_case1() {
+    fn test_find_all_unique() {
+        let TestCase { g, iset, oset } = test_utils::CASE3.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY
+        };
+        let expected = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let all = find_all(g, iset, oset, planes, 10);
+        assert_eq!(all, vec![expected]);
+    }
+
+    #[test_log::test]
+    fn test_find_all_multiple() {
         let TestCase { g, iset, oset } = test_utils::CASE1.clone();
         let planes = measurements! {
             0: Plane::XY,
@@ -260,54 +2024,152 @@ mod tests {
             2: Plane::XY,
             3: Plane::XY
         };
-        let flen = g.len() - oset.len();
-        let (f, layer) = find(g, iset, oset, planes).unwrap();
-        assert_eq!(f.len(), flen);
-        assert_eq!(f[&0], Nodes::from([1]));
-        assert_eq!(f[&1], Nodes::from([2]));
-        assert_eq!(f[&2], Nodes::from([3]));
-        assert_eq!(f[&3], Nodes::from([4]));
-        assert_eq!(layer, vec![4, 3, 2, 1, 0]);
+        let expected = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let all = find_all(g.clone(), iset.clone(), oset.clone(), planes.clone(), 100);
+        // Free nodes: {4} for 2, {3, 4} for 1, {2, 3, 4} for 0
+        assert_eq!(all.len(), 1 << 6);
+        assert_eq!(all[0], expected);
+        for (i, (f, layer)) in all.iter().enumerate() {
+            assert_eq!(layer, &expected.1);
+            verify(
+                f.clone(),
+                layer.clone(),
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                planes.clone(),
+                false,
+                None,
+            )
+            .unwrap();
+            assert!(all[..i].iter().all(|(f_, _)| f_ != f));
+        }
+        let limited = find_all(g, iset, oset, planes, 10);
+        assert_eq!(limited, all[..10]);
     }
 
     #[test_log::test]
-    fn test_find_case2() {
-        let TestCase { g, iset, oset } = test_utils::CASE2.clone();
+    fn test_find_all_none() {
+        let TestCase { g, iset, oset } = test_utils::CASE5.clone();
+        let planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY
+        };
+        assert!(find_all(g, iset, oset, planes, 10).is_empty());
+    }
+
+    #[test_log::test]
+    fn test_canonicalize() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
         let planes = measurements! {
             0: Plane::XY,
             1: Plane::XY,
             2: Plane::XY,
             3: Plane::XY
         };
-        let flen = g.len() - oset.len();
-        let (f, layer) = find(g, iset, oset, planes).unwrap();
-        assert_eq!(f.len(), flen);
-        assert_eq!(f[&0], Nodes::from([2]));
-        assert_eq!(f[&1], Nodes::from([3]));
-        assert_eq!(f[&2], Nodes::from([4]));
-        assert_eq!(f[&3], Nodes::from([5]));
-        assert_eq!(layer, vec![2, 2, 1, 1, 0, 0]);
+        let all = find_all(g.clone(), iset.clone(), oset.clone(), planes.clone(), 100);
+        assert_eq!(all.len(), 1 << 6);
+        let canonical = |f: &GFlow| {
+            canonicalize(
+                f.clone(),
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                planes.clone(),
+            )
+            .unwrap()
+        };
+        let expected = canonical(&all[0].0);
+        assert_eq!(expected.1, all[0].1);
+        for (f, _) in &all {
+            assert_eq!(canonical(f), expected);
+        }
+        // Minimum corrections on the path
+        for (i, fi) in &expected.0 {
+            assert_eq!(fi, &Nodes::from([i + 1]));
+        }
+        // Not a gflow
+        let mut f = all[0].0.clone();
+        f.insert(0, Nodes::from([2]));
+        assert!(canonicalize(f, g, iset, oset, planes).is_err());
     }
 
     #[test_log::test]
-    fn test_find_case3() {
+    fn test_verify_tolerant() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
+        let mut planes = measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
+        };
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let check = |planes: &Planes, tolerant| {
+            let (f, layer, g, iset, oset) = (&f, &layer, &g, &iset, &oset);
+            verify(
+                f.clone(),
+                layer.clone(),
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                planes.clone(),
+                tolerant,
+                None,
+            )
+        };
+        // 4 in O
+        planes.insert(4, Plane::XY);
+        assert!(check(&planes, false).is_err());
+        check(&planes, true).unwrap();
+        // Still required for V\O
+        planes.remove(&3);
+        assert!(check(&planes, true).is_err());
+    }
+
+    #[test_log::test]
+    fn test_verify_progress() {
         let TestCase { g, iset, oset } = test_utils::CASE3.clone();
         let planes = measurements! {
             0: Plane::XY,
             1: Plane::XY,
             2: Plane::XY
         };
-        let flen = g.len() - oset.len();
-        let (f, layer) = find(g, iset, oset, planes).unwrap();
-        assert_eq!(f.len(), flen);
-        assert_eq!(f[&0], Nodes::from([4, 5]));
-        assert_eq!(f[&1], Nodes::from([3, 4, 5]));
-        assert_eq!(f[&2], Nodes::from([3, 5]));
-        assert_eq!(layer, vec![1, 1, 1, 0, 0, 0]);
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let total = f.len();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let check = |progress: PyObject| {
+                verify(
+                    f.clone(),
+                    layer.clone(),
+                    g.clone(),
+                    iset.clone(),
+                    oset.clone(),
+                    planes.clone(),
+                    false,
+                    Some(progress),
+                )
+            };
+            let calls = PyList::empty_bound(py);
+            let append = calls.getattr("append").unwrap();
+            let progress = py
+                .eval_bound("lambda append: lambda k, n: append((k, n))", None, None)
+                .unwrap()
+                .call1((append,))
+                .unwrap();
+            check(progress.unbind()).unwrap();
+            let calls = calls.extract::<Vec<(usize, usize)>>().unwrap();
+            let expected = (1..=total).map(|k| (k, total)).collect::<Vec<_>>();
+            assert_eq!(calls, expected);
+            // Exceptions from the callback are not converted to ValueError
+            let progress = py.eval_bound("lambda k, n: 1 // 0", None, None).unwrap();
+            let err = check(progress.unbind()).unwrap_err();
+            assert!(err.is_instance_of::<PyZeroDivisionError>(py));
+        });
     }
 
     #[test_log::test]
-    fn test_find_case4() {
+    fn test_verify_corrections() {
         let TestCase { g, iset, oset } = test_utils::CASE4.clone();
         let planes = measurements! {
             0: Plane::XY,
@@ -315,58 +2177,343 @@ mod tests {
             2: Plane::XZ,
             3: Plane::YZ
         };
-        let flen = g.len() - oset.len();
-        let (f, layer) = find(g, iset, oset, planes).unwrap();
-        assert_eq!(f.len(), flen);
-        assert_eq!(f[&0], Nodes::from([2]));
-        assert_eq!(f[&1], Nodes::from([5]));
-        assert_eq!(f[&2], Nodes::from([2, 4]));
-        assert_eq!(f[&3], Nodes::from([3]));
-        assert_eq!(layer, vec![2, 2, 1, 1, 0, 0]);
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        assert_eq!(utils::compute_layer(&f, &g).unwrap(), layer);
+        verify_corrections(f, g, iset, oset, planes).unwrap();
     }
 
     #[test_log::test]
-    fn test_find_case5() {
-        let TestCase { g, iset, oset } = test_utils::CASE5.clone();
+    fn test_verify_corrections_cyclic() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
         let planes = measurements! {
             0: Plane::XY,
-            1: Plane::XY
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
         };
-        assert!(find(g, iset, oset, planes).is_none());
+        // 1 -> 2 -> 1
+        let f = GFlow::from([
+            (0, Nodes::from([1])),
+            (1, Nodes::from([2])),
+            (2, Nodes::from([1])),
+            (3, Nodes::from([4])),
+        ]);
+        assert!(utils::compute_layer(&f, &g).is_err());
+        assert!(verify_corrections(f, g, iset, oset, planes).is_err());
     }
 
     #[test_log::test]
-    fn test_find_case6() {
-        let TestCase { g, iset, oset } = test_utils::CASE6.clone();
+    fn test_find_with_forbidden_odd() {
+        let TestCase { g, iset, oset } = test_utils::CASE1.clone();
         let planes = measurements! {
             0: Plane::XY,
             1: Plane::XY,
             2: Plane::XY,
             3: Plane::XY
         };
-        assert!(find(g, iset, oset, planes).is_none());
+        let args = (g.clone(), iset.clone(), oset.clone(), planes.clone());
+        let find_forbidden = |forbidden: Nodes| {
+            let (g, iset, oset, planes) = args.clone();
+            find_with_forbidden_odd(g, iset, oset, planes, forbidden)
+        };
+        // Relaxed
+        let expected = find(g.clone(), iset, oset, planes).unwrap();
+        assert_eq!(find_forbidden(Nodes::new()).unwrap(), expected);
+        // f(0) = {1} is no longer allowed as Odd({1}) = {0, 2}
+        let (f, layer) = find_forbidden(Nodes::from([2])).unwrap();
+        assert_eq!(layer, expected.1);
+        for (i, fi) in &f {
+            let odd = utils::odd_neighbors(&g, fi);
+            assert!(*i == 2 || !odd.contains(&2));
+        }
+        // Odd(f(2)) always contains 4
+        assert!(find_forbidden(Nodes::from([4])).is_none());
     }
 
     #[test_log::test]
-    fn test_find_case7() {
-        let TestCase { g, iset, oset } = test_utils::CASE7.clone();
+    fn test_correctable() {
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
         let planes = measurements! {
-            0: Plane::YZ,
-            1: Plane::XZ,
-            2: Plane::XY,
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XZ,
             3: Plane::YZ
         };
-        assert!(find(g, iset, oset, planes).is_none());
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), planes).unwrap();
+        // 2 is corrected in the first layer
+        assert_eq!(layer[2], 1);
+        let f2 = correctable(g.clone(), iset.clone(), oset.clone(), 2, Plane::XZ);
+        assert_eq!(f2.as_ref(), f.get(&2));
+        // 0 needs the nodes corrected later
+        assert!(layer[0] > 1);
+        assert!(correctable(g, iset, oset, 0, Plane::XY).is_none());
     }
 
     #[test_log::test]
-    fn test_find_case8() {
-        let TestCase { g, iset, oset } = test_utils::CASE8.clone();
+    fn test_check_definition_bitset() {
+        let cases = [
+            &*test_utils::CASE0,
+            &*test_utils::CASE1,
+            &*test_utils::CASE2,
+            &*test_utils::CASE3,
+            &*test_utils::CASE4,
+            &*test_utils::CASE5,
+            &*test_utils::CASE6,
+            &*test_utils::CASE7,
+        ];
+        let root = |r: anyhow::Result<()>| r.map_err(|e| e.root_cause().to_string());
+        for TestCase { g, iset, oset } in cases {
+            let planes = (0..g.len())
+                .filter(|u| !oset.contains(u))
+                .map(|u| (u, Plane::XY))
+                .collect::<Planes>();
+            let Some((f, layer)) = find(g.clone(), iset.clone(), oset.clone(), planes.clone())
+            else {
+                continue;
+            };
+            check_definition_bitset(&f, &layer, g, &planes, |_| Ok(())).unwrap();
+            // Flat layer, wrong planes, and perturbed corrections
+            let flat = vec![0; g.len()];
+            let yz = planes.keys().map(|&u| (u, Plane::YZ)).collect::<Planes>();
+            for (u, v) in itertools::iproduct!(f.keys().copied(), 0..g.len()) {
+                let mut f_ = f.clone();
+                f_.get_mut(&u).unwrap().insert(v);
+                for (f, layer, planes) in [
+                    (&f, &flat, &planes),
+                    (&f, &layer, &yz),
+                    (&f_, &layer, &planes),
+                ] {
+                    assert_eq!(
+                        root(check_definition_bitset(f, layer, g, planes, |_| Ok(()))),
+                        root(check_definition(f, layer, g, planes))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test_log::test]
+    fn test_find_from_dict() {
+        // CASE4 with node u renamed to 10 * u + 1
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        let rename = |u: usize| 10 * u + 1;
+        let adj = g
+            .iter()
+            .enumerate()
+            .map(|(u, gu)| (rename(u), gu.iter().map(|&v| rename(v)).collect::<Nodes>()))
+            .collect::<hashbrown::HashMap<_, _>>();
         let planes = measurements! {
-            0: Plane::YZ,
-            1: Plane::XZ,
-            2: Plane::XY
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XZ,
+            3: Plane::YZ
         };
-        assert!(find(g, iset, oset, planes).is_none());
+        let (f, layer) = find(g, iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let iset_ = iset.iter().map(|&u| rename(u)).collect::<Nodes>();
+        let oset_ = oset.iter().map(|&u| rename(u)).collect::<Nodes>();
+        let planes_ = planes
+            .iter()
+            .map(|(&u, &pu)| (rename(u), pu))
+            .collect::<Planes>();
+        let (f_, layer_) =
+            find_from_dict(adj.clone(), iset_.clone(), oset_.clone(), planes_.clone())
+                .unwrap()
+                .unwrap();
+        for (u, &lu) in layer.iter().enumerate() {
+            assert_eq!(layer_[&rename(u)], lu);
+        }
+        for (i, fi) in &f {
+            assert_eq!(f_[&rename(*i)], fi.iter().map(|&j| rename(j)).collect());
+        }
+        // Asymmetric
+        let mut adj_ng = adj.clone();
+        adj_ng.get_mut(&1).unwrap().insert(31);
+        assert!(find_from_dict(adj_ng, iset_.clone(), oset_.clone(), planes_.clone()).is_err());
+        // Unknown node
+        assert!(find_from_dict(
+            adj.clone(),
+            iset_.clone(),
+            Nodes::from([2]),
+            planes_.clone()
+        )
+        .is_err());
+        // Missing plane
+        let mut planes_ng = planes_;
+        planes_ng.remove(&rename(2));
+        let err = find_from_dict(adj, iset_, oset_, planes_ng).unwrap_err();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|_| {
+            assert!(err.to_string().contains("plane of 21 not specified"));
+        });
+    }
+
+    #[test_log::test]
+    fn test_find_hopeless() {
+        // 0 - 1 - 2 - 3 - 4, with 0 and 1 as inputs
+        let TestCase { g, oset, .. } = test_utils::CASE1.clone();
+        let iset = Nodes::from([0, 1]);
+        let xy = single_planes(&measurements! {
+            0: Plane::XY,
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY
+        });
+        let ocset = OrderedNodes::from([0, 1, 2, 3]);
+        assert_eq!(find_hopeless(&g, &iset, &ocset, &xy), Some(0));
+        // Aborts before solving any system
+        let mut aux = FindAux::default();
+        assert!(find_impl(&g, &iset, &oset, &xy, &mut aux).is_none());
+        assert_eq!(aux.stats, FindStats::default());
+        // Not hopeless if 0 may be measured in YZ
+        let mut allowed = xy.clone();
+        allowed.insert(0, vec![Plane::XY, Plane::YZ]);
+        assert_eq!(find_hopeless(&g, &iset, &ocset, &allowed), None);
+        // Not hopeless unless all the neighbors are inputs
+        let iset = test_utils::CASE1.iset.clone();
+        assert_eq!(find_hopeless(&g, &iset, &ocset, &xy), None);
+        assert!(find_impl(&g, &iset, &oset, &xy, &mut FindAux::default()).is_some());
+    }
+
+    #[test_log::test]
+    fn test_rank_bound() {
+        // Not hopeless, but 1 and 2 are the only ones correctable in the first layer
+        let mut g = vec![Nodes::new(); 8];
+        for (u, v) in [
+            (0, 4),
+            (0, 5),
+            (0, 6),
+            (0, 7),
+            (1, 3),
+            (1, 5),
+            (2, 5),
+            (2, 6),
+            (3, 4),
+            (3, 7),
+            (4, 5),
+            (5, 6),
+            (5, 7),
+        ] {
+            g[u].insert(v);
+            g[v].insert(u);
+        }
+        let iset = Nodes::from([3, 4, 7]);
+        let oset = Nodes::from([0, 5, 6]);
+        let xy = single_planes(&measurements! {
+            1: Plane::XY,
+            2: Plane::XY,
+            3: Plane::XY,
+            4: Plane::XY,
+            7: Plane::XY
+        });
+        let ocset = (0..8)
+            .filter(|u| !oset.contains(u))
+            .collect::<OrderedNodes>();
+        assert_eq!(find_hopeless(&g, &iset, &ocset, &xy), None);
+        let mut aux = FindAux {
+            kernels: Some(Vec::new()),
+            ..Default::default()
+        };
+        assert!(find_impl(&g, &iset, &oset, &xy, &mut aux).is_none());
+        // Aborted at the top of the second layer, before the third one
+        assert_eq!(aux.kernels.unwrap().len(), 2);
+        assert!(aux.ranks.contains_key(&1) && aux.ranks.contains_key(&2));
+        assert_eq!(aux.ranks.len(), 2);
+        // R = {3, 4, 7} and X = {} with rank 1 in the second layer
+        let ocset = OrderedNodes::from([3, 4, 7]);
+        assert!(rank_bound_violated(&iset, &ocset, &xy, 1));
+        assert!(!rank_bound_violated(&iset, &ocset, &xy, 3));
+        let planes = xy.iter().map(|(&u, pu)| (u, pu[0])).collect::<Planes>();
+        assert!(!exists(g, iset, oset, planes));
+    }
+
+    #[test_log::test]
+    fn test_exists() {
+        for c in [
+            &test_utils::CASE0,
+            &test_utils::CASE1,
+            &test_utils::CASE2,
+            &test_utils::CASE3,
+            &test_utils::CASE4,
+            &test_utils::CASE5,
+            &test_utils::CASE6,
+            &test_utils::CASE7,
+            &test_utils::CASE8,
+        ] {
+            let TestCase { g, iset, oset } = (*c).clone();
+            for p in [Plane::XY, Plane::YZ, Plane::XZ] {
+                let planes = (0..g.len())
+                    .filter(|u| !oset.contains(u))
+                    .map(|u| (u, p))
+                    .collect::<Planes>();
+                let expected =
+                    find(g.clone(), iset.clone(), oset.clone(), planes.clone()).is_some();
+                assert_eq!(
+                    exists(g.clone(), iset.clone(), oset.clone(), planes),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test_log::test]
+    fn test_find_min() {
+        let total = |f: &GFlow| f.values().map(Nodes::len).sum::<usize>();
+        for c in [
+            &test_utils::CASE0,
+            &test_utils::CASE1,
+            &test_utils::CASE2,
+            &test_utils::CASE3,
+            &test_utils::CASE4,
+            &test_utils::CASE5,
+            &test_utils::CASE6,
+            &test_utils::CASE7,
+            &test_utils::CASE8,
+        ] {
+            let TestCase { g, iset, oset } = (*c).clone();
+            for p in [Plane::XY, Plane::YZ, Plane::XZ] {
+                let planes = (0..g.len())
+                    .filter(|u| !oset.contains(u))
+                    .map(|u| (u, p))
+                    .collect::<Planes>();
+                let expected = find(g.clone(), iset.clone(), oset.clone(), planes.clone());
+                let actual = find_min(g.clone(), iset.clone(), oset.clone(), planes.clone());
+                let (Some((f0, layer0)), Some((f, layer))) = (expected, actual.clone()) else {
+                    assert!(actual.is_none());
+                    continue;
+                };
+                assert_eq!(layer, layer0);
+                assert!(total(&f) <= total(&f0));
+                verify(
+                    f,
+                    layer,
+                    g.clone(),
+                    iset.clone(),
+                    oset.clone(),
+                    planes,
+                    false,
+                    None,
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    #[test_log::test]
+    fn test_find_min_optimal() {
+        let total = |f: &GFlow| f.values().map(Nodes::len).sum::<usize>();
+        for c in [&test_utils::CASE1, &test_utils::CASE2, &test_utils::CASE3] {
+            let TestCase { g, iset, oset } = (*c).clone();
+            let planes = (0..g.len())
+                .filter(|u| !oset.contains(u))
+                .map(|u| (u, Plane::XY))
+                .collect::<Planes>();
+            let (f, _) = find_min(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+            // Nodes are independent as long as the layer is fixed
+            let best = find_all(g, iset, oset, planes, 1 << 16)
+                .iter()
+                .map(|(f, _)| total(f))
+                .min();
+            assert_eq!(Some(total(&f)), best);
+        }
     }
 }
@@ -7,8 +7,19 @@ use std::{
 };
 
 use fixedbitset::FixedBitSet;
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
 
-use crate::common::{Graph, Nodes, OrderedNodes};
+use crate::common::{Graph, Layer, Nodes, OrderedNodes};
+
+/// Packs `values` into `array.array` of typecode `"Q"`.
+pub fn u64_array<'py>(py: Python<'py>, values: &[usize]) -> PyResult<Bound<'py, PyAny>> {
+    let bytes = values
+        .iter()
+        .flat_map(|&v| (v as u64).to_ne_bytes())
+        .collect::<Vec<_>>();
+    let array = py.import_bound("array")?.getattr("array")?;
+    array.call1(("Q", PyBytes::new_bound(py, &bytes)))
+}
 
 /// Computes the odd neighbors of the vertices in `kset`.
 ///
@@ -23,6 +34,191 @@ pub fn odd_neighbors(g: &Graph, kset: &Nodes) -> Nodes {
     work
 }
 
+/// Converts a validation error into `ValueError`, keeping the whole context chain.
+#[allow(clippy::needless_pass_by_value)]
+pub fn to_pyerr(e: anyhow::Error) -> PyErr {
+    PyValueError::new_err(format!("{e:#}"))
+}
+
+/// Computes `Odd(f(i))` for each `i` in the domain of `f` using the bitset adjacency.
+pub fn odd_neighbors_map(
+    f: &hashbrown::HashMap<usize, Nodes>,
+    g: &Graph,
+) -> hashbrown::HashMap<usize, Nodes> {
+    let gb = bitset_graph(g);
+    let mut work = FixedBitSet::with_capacity(g.len());
+    f.iter()
+        .map(|(&i, fi)| {
+            work.clear();
+            for &v in fi {
+                work ^= &gb[v];
+            }
+            (i, work.ones().collect())
+        })
+        .collect()
+}
+
+/// Computes the layer induced by the correction sets.
+///
+/// Nodes outside the domain of `f` are in the layer `0`, and the others are in the layer `1 + max(layer[j])`,
+/// where `j` runs over `f(i)` and `Odd(f(i))` other than `i`.
+///
+/// # Returns
+///
+/// Returns `Err` if the induced order is cyclic.
+pub fn compute_layer(f: &hashbrown::HashMap<usize, Nodes>, g: &Graph) -> anyhow::Result<Layer> {
+    const UNVISITED: u8 = 0;
+    const VISITING: u8 = 1;
+    const DONE: u8 = 2;
+    let n = g.len();
+    let odd = odd_neighbors_map(f, g);
+    let mut succ = vec![Vec::new(); n];
+    for (&i, fi) in f {
+        let mut si = fi.union(&odd[&i]).copied().collect::<Vec<_>>();
+        si.retain(|&j| j != i);
+        succ[i] = si;
+    }
+    let mut state = vec![UNVISITED; n];
+    let mut layer = vec![0; n];
+    let mut stack = Vec::new();
+    for &root in f.keys() {
+        if state[root] != UNVISITED {
+            continue;
+        }
+        state[root] = VISITING;
+        stack.push((root, 0));
+        while let Some((u, k)) = stack.last_mut() {
+            let u = *u;
+            if let Some(&v) = succ[u].get(*k) {
+                *k += 1;
+                match state[v] {
+                    UNVISITED => {
+                        state[v] = VISITING;
+                        stack.push((v, 0));
+                    }
+                    VISITING => {
+                        let err = anyhow::anyhow!("layer computation failed")
+                            .context(format!("cyclic dependency detected: {u} -> {v}"));
+                        return Err(err);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if f.contains_key(&u) {
+                layer[u] = 1 + succ[u].iter().map(|&v| layer[v]).max().unwrap_or_default();
+            }
+            state[u] = DONE;
+            stack.pop();
+        }
+    }
+    Ok(layer)
+}
+
+/// Reduces `x` to the minimum of `x + span(basis)`.
+///
+/// Bitsets are compared as binary numbers, with the bit `i` weighted by `2^i`.
+pub fn reduce_by_span(x: &mut FixedBitSet, basis: &[FixedBitSet]) {
+    // Fully-reduced echelon form as (leading bit, vector)
+    let mut ech = Vec::<(usize, FixedBitSet)>::with_capacity(basis.len());
+    for b in basis {
+        let mut v = b.clone();
+        for (lead, e) in &ech {
+            if v[*lead] {
+                v ^= e;
+            }
+        }
+        let Some(lead) = v.ones().next_back() else {
+            continue;
+        };
+        for (_, e) in &mut ech {
+            if e[lead] {
+                *e ^= &v;
+            }
+        }
+        ech.push((lead, v));
+    }
+    for (lead, e) in &ech {
+        if x[*lead] {
+            *x ^= e;
+        }
+    }
+}
+
+/// Computes the number of layers.
+pub fn depth(layer: &Layer) -> usize {
+    layer.iter().max().map_or(0, |&l| l + 1)
+}
+
+/// Computes the ordering constraints induced by the correction sets.
+///
+/// Returns sorted `(i, j)` such that `j` is in `f(i)` or `Odd(f(i))`, `i != j`, and `layer[i] > layer[j]`.
+pub fn order_edges(
+    f: &hashbrown::HashMap<usize, Nodes>,
+    layer: &Layer,
+    g: &Graph,
+) -> Vec<(usize, usize)> {
+    let mut targets = OrderedNodes::new();
+    let mut edges = Vec::new();
+    for (&i, fi) in f {
+        targets.clear();
+        targets.extend(fi.iter().copied());
+        targets.extend(odd_neighbors(g, fi));
+        edges.extend(
+            targets
+                .iter()
+                .filter(|&&j| i != j && layer[i] > layer[j])
+                .map(|&j| (i, j)),
+        );
+    }
+    edges.sort_unstable();
+    edges
+}
+
+/// Encodes the adjacency list as one bitset row per node.
+pub fn bitset_graph(g: &Graph) -> Vec<FixedBitSet> {
+    let n = g.len();
+    g.iter()
+        .map(|gu| {
+            let mut row = FixedBitSet::with_capacity(n);
+            row.extend(gu.iter().copied());
+            row
+        })
+        .collect()
+}
+
+/// Converts the graph into bitsets restricted to `nodes`.
+///
+/// # Note
+///
+/// - Bit `k` of the `u`-th row is set iff the `k`-th smallest node of `nodes` is adjacent to `u`.
+/// - Requires `g.len()` rows of `nodes.len()` bits, much smaller than `bitset_graph` if `nodes` is small.
+pub fn bitset_subgraph(g: &Graph, nodes: &OrderedNodes) -> Vec<FixedBitSet> {
+    let n2i = indexmap::<hashbrown::HashMap<_, _>>(nodes);
+    g.iter()
+        .map(|gu| {
+            let mut row = FixedBitSet::with_capacity(nodes.len());
+            row.extend(gu.iter().filter_map(|v| n2i.get(v).copied()));
+            row
+        })
+        .collect()
+}
+
+/// Counts the number of elements in `a & b`.
+pub fn intersection_count(a: &FixedBitSet, b: &FixedBitSet) -> usize {
+    a.intersection_count(b)
+}
+
+/// Writes `a & b` to `dst`.
+///
+/// # Note
+///
+/// - Reuses the allocation of `dst` if it has the same length as `a`.
+pub fn intersection_into(dst: &mut FixedBitSet, a: &FixedBitSet, b: &FixedBitSet) {
+    dst.clone_from(a);
+    dst.intersect_with(b);
+}
+
 /// Resizes `mat` to `mat.len()` x `ncols` and fills with zeros.
 pub fn zerofill(mat: &mut [FixedBitSet], ncols: usize) {
     let src = FixedBitSet::with_capacity(ncols);
@@ -31,6 +227,21 @@ pub fn zerofill(mat: &mut [FixedBitSet], ncols: usize) {
     }
 }
 
+/// Applies the local complementation at `u` to `g`, toggling the edges among the neighbors of `u`.
+pub fn local_complement(g: &mut Graph, u: usize) {
+    let nu = g[u].iter().copied().collect::<OrderedNodes>();
+    for &v in &nu {
+        for &w in nu.range(v + 1..) {
+            if g[v].remove(&w) {
+                g[w].remove(&v);
+            } else {
+                g[v].insert(w);
+                g[w].insert(v);
+            }
+        }
+    }
+}
+
 /// Helper trait for in-place set operations.
 pub trait InPlaceSetDiff<T> {
     /// Drops the elements from `other` from self.
@@ -151,7 +362,9 @@ impl Drop for ScopedExclude<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::internal::test_utils::{TestCase, CASE3};
+    use crate::internal::test_utils::{
+        TestCase, CASE0, CASE1, CASE2, CASE3, CASE4, CASE5, CASE6, CASE7, CASE8,
+    };
 
     #[test]
     fn test_odd_neighbors() {
@@ -178,4 +391,76 @@ mod tests {
             Nodes::from([1, 5])
         );
     }
+
+    #[test]
+    fn test_intersection() {
+        let cases = [
+            &*CASE0, &*CASE1, &*CASE2, &*CASE3, &*CASE4, &*CASE5, &*CASE6, &*CASE7, &*CASE8,
+        ];
+        for TestCase { g, .. } in cases {
+            let gb = bitset_graph(g);
+            let mut dst = FixedBitSet::new();
+            for (u, v) in itertools::iproduct!(0..g.len(), 0..g.len()) {
+                let expected = g[u].intersection(&g[v]).copied().collect::<Nodes>();
+                assert_eq!(intersection_count(&gb[u], &gb[v]), expected.len());
+                intersection_into(&mut dst, &gb[u], &gb[v]);
+                assert_eq!(dst.ones().collect::<Nodes>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitset_subgraph() {
+        let TestCase { g, oset, .. } = &*CASE4;
+        let nodes = (0..g.len())
+            .filter(|u| !oset.contains(u))
+            .collect::<OrderedNodes>();
+        let cols = nodes.iter().copied().collect::<Vec<_>>();
+        let gb = bitset_subgraph(g, &nodes);
+        for (u, gu) in g.iter().enumerate() {
+            assert_eq!(gb[u].len(), nodes.len());
+            let expected = gu.iter().filter(|v| nodes.contains(v)).copied();
+            assert_eq!(
+                gb[u].ones().map(|k| cols[k]).collect::<Nodes>(),
+                expected.collect()
+            );
+        }
+    }
+
+    #[test]
+    fn test_local_complement() {
+        let mut g = CASE6.g.clone();
+        local_complement(&mut g, 1);
+        assert_eq!(g[0], Nodes::from([1, 2, 4]));
+        assert_eq!(g[2], Nodes::from([0, 1, 3, 4]));
+        assert_eq!(g[4], Nodes::from([0, 1, 2]));
+        assert_eq!(g[1], CASE6.g[1]);
+        assert_eq!(g[3], CASE6.g[3]);
+        local_complement(&mut g, 1);
+        assert_eq!(g, CASE6.g);
+    }
+
+    #[test]
+    fn test_reduce_by_span() {
+        let bits = |s: &str| {
+            let mut x = FixedBitSet::with_capacity(s.len());
+            for (i, c) in s.chars().enumerate() {
+                x.set(i, c == '1');
+            }
+            x
+        };
+        let basis = [bits("1101"), bits("0111"), bits("1010")];
+        // span = {0000, 1101, 0111, 1010}
+        for x in ["1101", "0111", "1010", "0000"] {
+            let mut x = bits(x);
+            reduce_by_span(&mut x, &basis);
+            assert_eq!(x, bits("0000"));
+        }
+        // 1100 = 3 is the minimum of the coset
+        for x in ["0001", "1100", "0110", "1011"] {
+            let mut x = bits(x);
+            reduce_by_span(&mut x, &basis);
+            assert_eq!(x, bits("1100"));
+        }
+    }
 }
@@ -51,6 +51,88 @@ pub fn check_graph(g: &Graph, iset: &Nodes, oset: &Nodes) -> anyhow::Result<()>
     Ok(())
 }
 
+/// Checks if the node references are consistent with the number of nodes.
+///
+/// # Returns
+///
+/// Returns `Err` if any of the following conditions are met:
+///
+/// - `iset`/`oset`/`meas` contains nodes other than `0..n`.
+/// - Some node in `0..n` is neither in `meas` nor in `oset`.
+///
+/// # Note
+///
+/// - Offending nodes are reported in ascending order.
+pub fn check_node_count(n: usize, iset: &Nodes, oset: &Nodes, meas: &Nodes) -> anyhow::Result<()> {
+    for (name, nodes) in [("iset", iset), ("oset", oset), ("meas", meas)] {
+        if let Some(u) = nodes.iter().copied().filter(|&u| u >= n).min() {
+            anyhow::bail!("node index out of range in {name}: {u} >= {n}");
+        }
+    }
+    if let Some(u) = (0..n).find(|u| !meas.contains(u) && !oset.contains(u)) {
+        anyhow::bail!("{u} is neither measured nor output");
+    }
+    Ok(())
+}
+
+/// Checks if the undirected edge list is valid.
+///
+/// # Returns
+///
+/// Returns `Err` if any of the following conditions are met:
+///
+/// - `edges` contains self-loops.
+/// - `edges` contains nodes other than `0..n`.
+/// - `edges` contains the same edge more than once, in either direction.
+pub fn check_edges(n: usize, edges: &[(usize, usize)]) -> anyhow::Result<()> {
+    let mut seen = hashbrown::HashSet::with_capacity(edges.len());
+    for &(u, v) in edges {
+        if u >= n || v >= n {
+            anyhow::bail!("node index out of range: ({u}, {v})");
+        }
+        if u == v {
+            anyhow::bail!("self-loop detected: {u}");
+        }
+        if !seen.insert((u.min(v), u.max(v))) {
+            anyhow::bail!("duplicate edge detected: ({u}, {v})");
+        }
+    }
+    Ok(())
+}
+
+/// Checks if the directed arc list encodes an undirected graph.
+///
+/// # Returns
+///
+/// Returns `Err` if any of the following conditions are met:
+///
+/// - `arcs` contains self-loops.
+/// - `arcs` contains nodes other than `0..n`.
+/// - `(u, v)` appears a different number of times than `(v, u)`.
+/// - `arcs` contains the same arc more than once.
+pub fn check_arcs(n: usize, arcs: &[(usize, usize)]) -> anyhow::Result<()> {
+    let mut count = hashbrown::HashMap::<_, usize>::with_capacity(arcs.len());
+    for &(u, v) in arcs {
+        if u >= n || v >= n {
+            anyhow::bail!("node index out of range: ({u}, {v})");
+        }
+        if u == v {
+            anyhow::bail!("self-loop detected: {u}");
+        }
+        *count.entry((u, v)).or_default() += 1;
+    }
+    for (&(u, v), &c) in &count {
+        let rc = count.get(&(v, u)).copied().unwrap_or_default();
+        if c != rc {
+            anyhow::bail!("inconsistent multiplicity: ({u}, {v}) x {c} but ({v}, {u}) x {rc}");
+        }
+        if c > 1 {
+            anyhow::bail!("duplicate edge detected: ({u}, {v}) x {c}");
+        }
+    }
+    Ok(())
+}
+
 /// Checks if the layer-zero nodes are correctly chosen.
 ///
 /// # Arguments
@@ -77,38 +159,130 @@ pub fn check_initial(layer: &Layer, oset: &Nodes, iff: bool) -> anyhow::Result<(
     Ok(())
 }
 
+/// Node sets derived from the inputs and outputs of a problem instance.
+///
+/// # Note
+///
+/// - Built once per instance and shared by the search and the validations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowSets {
+    /// All nodes.
+    pub vset: Nodes,
+    /// Non-input nodes.
+    pub icset: Nodes,
+    /// Non-output nodes.
+    pub ocset: Nodes,
+}
+
+impl FlowSets {
+    pub fn new(n: usize, iset: &Nodes, oset: &Nodes) -> Self {
+        let vset = (0..n).collect::<Nodes>();
+        let icset = &vset - iset;
+        let ocset = &vset - oset;
+        Self { vset, icset, ocset }
+    }
+}
+
 /// Checks if the domain of `f` is in `vset - oset` and the codomain is in `vset - iset`.
 ///
 /// # Arguments
 ///
 /// - `f_flatiter`: Flow, gflow, or pflow as `impl Iterator<Item = (&usize, &usize)>`.
-/// - `vset`: All nodes.
-/// - `iset`: Input nodes.
-/// - `oset`: Output nodes.
+/// - `sets`: Node sets of the instance.
 ///
 /// # Note
 ///
 /// It is allowed for `f[i]` to contain `i`, even if `i` is in `iset`.
 pub fn check_domain<'a, 'b>(
     f_flatiter: impl Iterator<Item = (&'a usize, &'b usize)>,
-    vset: &Nodes,
-    iset: &Nodes,
-    oset: &Nodes,
+    sets: &FlowSets,
 ) -> anyhow::Result<()> {
-    let icset = vset - iset;
-    let ocset = vset - oset;
     let mut dom = Nodes::new();
     for (&i, &fi) in f_flatiter {
         dom.insert(i);
-        if i != fi && !icset.contains(&fi) {
+        if i != fi && !sets.icset.contains(&fi) {
             let err = anyhow::anyhow!("domain check failed").context(format!("{fi} not in V\\I"));
             return Err(err);
         }
     }
-    if dom != ocset {
+    if dom != sets.ocset {
         let err = anyhow::anyhow!("domain check failed")
             .context(format!("invalid domain: {dom:?} != V\\O"));
         return Err(err);
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_edges() {
+        check_edges(4, &[(0, 1), (1, 2), (3, 2)]).unwrap();
+        // Duplicated
+        assert!(check_edges(4, &[(0, 1), (1, 2), (0, 1)]).is_err());
+        assert!(check_edges(4, &[(0, 1), (1, 2), (1, 0)]).is_err());
+        // Self-loop
+        assert!(check_edges(4, &[(0, 1), (2, 2)]).is_err());
+        // Out of range
+        assert!(check_edges(4, &[(0, 1), (2, 4)]).is_err());
+    }
+
+    #[test]
+    fn test_check_arcs() {
+        check_arcs(3, &[(0, 1), (1, 0), (1, 2), (2, 1)]).unwrap();
+        // One-directional
+        assert!(check_arcs(3, &[(0, 1), (1, 0), (1, 2)]).is_err());
+        // Duplicated
+        assert!(check_arcs(3, &[(0, 1), (1, 0), (0, 1)]).is_err());
+        assert!(check_arcs(3, &[(0, 1), (1, 0), (0, 1), (1, 0)]).is_err());
+        // Self-loop
+        assert!(check_arcs(3, &[(1, 1)]).is_err());
+        // Out of range
+        assert!(check_arcs(3, &[(0, 3), (3, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_check_node_count() {
+        let (iset, oset, meas) = (Nodes::from([0]), Nodes::from([2]), Nodes::from([0, 1]));
+        check_node_count(3, &iset, &oset, &meas).unwrap();
+        let msg = |r: anyhow::Result<()>| r.unwrap_err().to_string();
+        assert_eq!(
+            msg(check_node_count(3, &Nodes::from([0, 5, 4]), &oset, &meas)),
+            "node index out of range in iset: 4 >= 3"
+        );
+        assert_eq!(
+            msg(check_node_count(3, &iset, &Nodes::from([2, 3]), &meas)),
+            "node index out of range in oset: 3 >= 3"
+        );
+        assert_eq!(
+            msg(check_node_count(3, &iset, &oset, &Nodes::from([0, 1, 3]))),
+            "node index out of range in meas: 3 >= 3"
+        );
+        assert_eq!(
+            msg(check_node_count(4, &iset, &oset, &meas)),
+            "3 is neither measured nor output"
+        );
+        assert_eq!(
+            msg(check_node_count(3, &iset, &oset, &Nodes::from([0]))),
+            "1 is neither measured nor output"
+        );
+    }
+
+    #[test]
+    fn test_check_domain() {
+        let sets = FlowSets::new(3, &Nodes::from([0]), &Nodes::from([2]));
+        assert_eq!(sets.icset, Nodes::from([1, 2]));
+        assert_eq!(sets.ocset, Nodes::from([0, 1]));
+        check_domain([(&0, &1), (&1, &2)].into_iter(), &sets).unwrap();
+        // i in f(i) allowed even for inputs
+        check_domain([(&0, &0), (&1, &2)].into_iter(), &sets).unwrap();
+        // Input in codomain
+        assert!(check_domain([(&0, &1), (&1, &0)].into_iter(), &sets).is_err());
+        // Output in domain
+        assert!(check_domain([(&0, &1), (&1, &2), (&2, &1)].into_iter(), &sets).is_err());
+        // Missing node
+        assert!(check_domain([(&0, &1)].into_iter(), &sets).is_err());
+    }
+}
@@ -6,13 +6,15 @@
 #[macro_use]
 mod internal;
 
+// MEMO: Modules with #[pyfunction]s returning PyResult allow clippy::useless_conversion
+//  False positive in the generated wrappers, which are siblings of the functions and out of reach of item-level allows
 pub mod common;
 pub mod flow;
 pub mod gflow;
 pub mod pflow;
 
-use gflow::Plane;
-use pflow::PPlane;
+use gflow::{FlowReport, Plane};
+use pflow::{PFlowResult, PFlowWorkspace, PPlane};
 use pyo3::prelude::*;
 
 // MEMO: Data verification is done in the Python layer
@@ -22,19 +24,133 @@ use pyo3::prelude::*;
 #[pyo3(name = "_impl")]
 #[allow(clippy::similar_names)]
 fn entrypoint(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // fastflow._impl.common
+    let mod_common = PyModule::new_bound(m.py(), "common")?;
+    mod_common.add_class::<common::FindStats>()?;
+    mod_common.add_class::<common::GF2System>()?;
+    mod_common.add_function(wrap_pyfunction!(common::solve_with_rank, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::solve_one, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::solve_int, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::nullity, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::invert, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::det_gf2, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::solve_sparse, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::solve_all_solutions, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::is_symmetric, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::find_asymmetry, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::stepwise_plan, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::graph_from_edges, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::to_edges, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::layer_array, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::check_node_count, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::past, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::depth_lower_bound, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(common::dump_case, &mod_common)?)?;
+    mod_common.add_function(wrap_pyfunction!(
+        common::odd_neighbors_of_flow,
+        &mod_common
+    )?)?;
+    m.add_submodule(&mod_common)?;
     // fastflow._impl.flow
     let mod_flow = PyModule::new_bound(m.py(), "flow")?;
     mod_flow.add_function(wrap_pyfunction!(flow::find, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::find_or_blocker, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::find_bounded, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::exists, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::dag, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::depth, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::layers, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::verify_all, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::check_definition, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::as_gflow, &mod_flow)?)?;
+    mod_flow.add_function(wrap_pyfunction!(flow::inverse, &mod_flow)?)?;
     m.add_submodule(&mod_flow)?;
     // fastflow._impl.gflow
     let mod_gflow = PyModule::new_bound(m.py(), "gflow")?;
     mod_gflow.add_class::<Plane>()?;
+    mod_gflow.add_class::<FlowReport>()?;
     mod_gflow.add_function(wrap_pyfunction!(gflow::find, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::find_verbose, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::forced_nodes, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::find_multiplane, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::find_all, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::find_csr, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::exists, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::find_min, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::correctable, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::layer_matrix, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::find_from_dict, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(
+        gflow::find_with_forbidden_odd,
+        &mod_gflow
+    )?)?;
+    mod_gflow.add_function(wrap_pyfunction!(
+        gflow::find_with_local_complements,
+        &mod_gflow
+    )?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::canonicalize, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::verify, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::is_valid_order, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::correction_volume, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::verify_maximal, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::verify_corrections, &mod_gflow)?)?;
+    mod_gflow.add_function(wrap_pyfunction!(gflow::check_node_correction, &mod_gflow)?)?;
     m.add_submodule(&mod_gflow)?;
     // fastflow._impl.pflow
     let mod_pflow = PyModule::new_bound(m.py(), "pflow")?;
     mod_pflow.add_class::<PPlane>()?;
+    mod_pflow.add_class::<PFlowWorkspace>()?;
+    mod_pflow.add_class::<PFlowResult>()?;
     mod_pflow.add_function(wrap_pyfunction!(pflow::find, &mod_pflow)?)?;
+    mod_pflow.add_function(wrap_pyfunction!(pflow::find_verbose, &mod_pflow)?)?;
+    mod_pflow.add_function(wrap_pyfunction!(pflow::find_with_stats, &mod_pflow)?)?;
+    mod_pflow.add_function(wrap_pyfunction!(pflow::find_from_angles, &mod_pflow)?)?;
+    mod_pflow.add_function(wrap_pyfunction!(pflow::verify, &mod_pflow)?)?;
+    mod_pflow.add_function(wrap_pyfunction!(pflow::verify_maximal, &mod_pflow)?)?;
+    mod_pflow.add_function(wrap_pyfunction!(pflow::check_embedding, &mod_pflow)?)?;
     m.add_submodule(&mod_pflow)?;
+    // Needed for pickle to locate the classes
+    let modules = m.py().import_bound("sys")?.getattr("modules")?;
+    modules.set_item("fastflow._impl.pflow", &mod_pflow)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lists the top-level functions and classes declared in a stub file.
+    fn declared(stub: &str) -> Vec<&str> {
+        stub.lines()
+            .filter_map(|line| {
+                line.strip_prefix("def ")
+                    .or_else(|| line.strip_prefix("class "))
+            })
+            .map(|rest| rest.split(['(', ':']).next().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_stubs_registered() {
+        let stubs = [
+            (
+                "common",
+                include_str!("../python/fastflow/_impl/common.pyi"),
+            ),
+            ("flow", include_str!("../python/fastflow/_impl/flow.pyi")),
+            ("gflow", include_str!("../python/fastflow/_impl/gflow.pyi")),
+            ("pflow", include_str!("../python/fastflow/_impl/pflow.pyi")),
+        ];
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let m = PyModule::new_bound(py, "_impl").unwrap();
+            entrypoint(&m).unwrap();
+            for (name, stub) in stubs {
+                let sub = m.getattr(name).unwrap();
+                for item in declared(stub) {
+                    assert!(sub.hasattr(item).unwrap(), "{name}.{item} not registered");
+                }
+            }
+        });
+    }
+}
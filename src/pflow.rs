@@ -1,21 +1,23 @@
 //! Maximally-delayed Pauli flow algorithm.
+#![allow(clippy::useless_conversion)]
 
-use std::iter;
+use std::{iter, slice};
 
 use fixedbitset::FixedBitSet;
 use hashbrown;
 use pyo3::prelude::*;
 
 use crate::{
-    common::{Graph, Layer, Nodes, OrderedNodes},
+    common::{FindStats, Graph, Layer, Nodes, OrderedNodes},
+    gflow::Plane,
     internal::{
         gf2_linalg::GF2Solver,
         utils::{self, InPlaceSetDiff, ScopedExclude, ScopedInclude},
-        validate,
+        validate::{self, FlowSets},
     },
 };
 
-#[pyclass(eq, hash, frozen)]
+#[pyclass(eq, hash, frozen, module = "fastflow._impl.pflow")]
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 /// Measurement plane or Pauli index.
 pub enum PPlane {
@@ -33,9 +35,141 @@ pub enum PPlane {
     Z,
 }
 
+#[pymethods]
+impl PPlane {
+    /// Classifies the measurement given by `plane` and `angle`.
+    ///
+    /// # Arguments
+    ///
+    /// - `plane`: Measurement plane.
+    /// - `angle`: Measurement angle in units of pi.
+    /// - `atol`: Absolute tolerance of `angle`, in units of pi.
+    ///
+    /// # Note
+    ///
+    /// - Angles are measured from the first axis toward the second one of X -> Y, Z -> Y, and Z -> X,
+    ///   for `XY`, `YZ`, and `XZ`, respectively.
+    /// - Returns `plane` as is unless `angle` is a multiple of 1/2 within `atol`.
+    /// - Non-finite `angle` or NaN `atol` never counts as Pauli.
+    #[staticmethod]
+    #[must_use]
+    pub fn from_plane_angle(plane: Plane, angle: f64, atol: f64) -> Self {
+        let half = (2.0 * angle).round();
+        let dev = (angle - half / 2.0).abs();
+        // NaN if angle is not finite
+        if dev.is_nan() || atol.is_nan() || dev > atol {
+            return plane.into();
+        }
+        let even = half.rem_euclid(2.0) < 0.5;
+        match (plane, even) {
+            (Plane::XY, true) | (Plane::XZ, false) => Self::X,
+            (Plane::XY | Plane::YZ, false) => Self::Y,
+            (Plane::YZ | Plane::XZ, true) => Self::Z,
+        }
+    }
+
+    /// Pickles as a reference to the class attribute.
+    fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        let name = match slf.get() {
+            Self::XY => "XY",
+            Self::YZ => "YZ",
+            Self::XZ => "XZ",
+            Self::X => "X",
+            Self::Y => "Y",
+            Self::Z => "Z",
+        };
+        let py = slf.py();
+        let getattr = py.import_bound("builtins")?.getattr("getattr")?;
+        Ok((getattr, (slf.as_any().get_type(), name)).into_py(py))
+    }
+}
+
+impl From<Plane> for PPlane {
+    fn from(value: Plane) -> Self {
+        match value {
+            Plane::XY => Self::XY,
+            Plane::YZ => Self::YZ,
+            Plane::XZ => Self::XZ,
+        }
+    }
+}
+
 type PPlanes = hashbrown::HashMap<usize, PPlane>;
 type PFlow = hashbrown::HashMap<usize, Nodes>;
 
+#[pyclass(frozen, get_all, module = "fastflow._impl.pflow")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Pauli flow bundled with the data derived from it.
+pub struct PFlowResult {
+    /// Pauli flow function.
+    pub f: PFlow,
+    /// Layer of each node.
+    pub layer: Layer,
+    /// Number of layers.
+    pub depth: usize,
+    /// Branch that determined `f(i)` of each node `i` in `f`, one of `XY`, `YZ`, and `XZ`.
+    pub branch: PPlanes,
+}
+
+#[pymethods]
+impl PFlowResult {
+    #[new]
+    fn new(f: PFlow, layer: Layer, depth: usize, branch: PPlanes) -> Self {
+        Self {
+            f,
+            layer,
+            depth,
+            branch,
+        }
+    }
+
+    fn __getnewargs__(&self) -> (PFlow, Layer, usize, PPlanes) {
+        (
+            self.f.clone(),
+            self.layer.clone(),
+            self.depth,
+            self.branch.clone(),
+        )
+    }
+}
+
+/// Describes the clause of the measurement condition violated by `i`, if any.
+///
+/// `in_info` is `(i in f(i), i in Odd(f(i)))`.
+fn pplane_violation(i: usize, pi: PPlane, in_info: (bool, bool)) -> Option<String> {
+    let (in_f, in_odd) = in_info;
+    let (req_f, req_odd) = match pi {
+        PPlane::XY => (Some(false), Some(true)),
+        PPlane::YZ => (Some(true), Some(false)),
+        PPlane::XZ => (Some(true), Some(true)),
+        PPlane::X => (None, Some(true)),
+        PPlane::Z => (Some(true), None),
+        PPlane::Y if in_f == in_odd => {
+            let both = if in_f { "both" } else { "neither" };
+            return Some(format!(
+                "{i} in {both} f({i}) and Odd(f({i})), but Y requires exactly one"
+            ));
+        }
+        PPlane::Y => (None, None),
+    };
+    let fmt_in = |b: bool| if b { "in" } else { "not in" };
+    if let Some(req_f) = req_f.filter(|&r| r != in_f) {
+        return Some(format!(
+            "{i} {} f({i}), but {pi:?} requires {i} {} f({i})",
+            fmt_in(in_f),
+            fmt_in(req_f)
+        ));
+    }
+    if let Some(req_odd) = req_odd.filter(|&r| r != in_odd) {
+        return Some(format!(
+            "{i} {} Odd(f({i})), but {pi:?} requires {i} {} Odd(f({i}))",
+            fmt_in(in_odd),
+            fmt_in(req_odd)
+        ));
+    }
+    None
+}
+
 /// Checks the definition of Pauli flow.
 fn check_definition(f: &PFlow, layer: &Layer, g: &Graph, pplanes: &PPlanes) -> anyhow::Result<()> {
     anyhow::ensure!(
@@ -43,10 +177,12 @@ fn check_definition(f: &PFlow, layer: &Layer, g: &Graph, pplanes: &PPlanes) -> a
         "f and pplanes must have the same codomain"
     );
     for (&i, fi) in f {
-        let pi = pplanes[&i];
+        let Some(&pi) = pplanes.get(&i) else {
+            anyhow::bail!("pplane check failed: pplane of {i} not specified");
+        };
         for &fij in fi {
             match (i != fij, layer[i] <= layer[fij]) {
-                (true, true) if !matches!(pplanes[&fij], PPlane::X | PPlane::Y) => {
+                (true, true) if !matches!(pplanes.get(&fij), Some(PPlane::X | PPlane::Y)) => {
                     let err = anyhow::anyhow!("layer check failed")
                         .context(format!("neither {i} == {fij} nor {i} -> {fij}: fi"));
                     return Err(err);
@@ -58,7 +194,7 @@ fn check_definition(f: &PFlow, layer: &Layer, g: &Graph, pplanes: &PPlanes) -> a
         let odd_fi = utils::odd_neighbors(g, fi);
         for &j in &odd_fi {
             match (i != j, layer[i] <= layer[j]) {
-                (true, true) if !matches!(pplanes[&j], PPlane::Y | PPlane::Z) => {
+                (true, true) if !matches!(pplanes.get(&j), Some(PPlane::Y | PPlane::Z)) => {
                     let err = anyhow::anyhow!("layer check failed").context(format!(
                         "neither {i} == {j} nor {i} -> {j}: odd_neighbors(g, fi)"
                     ));
@@ -76,45 +212,165 @@ fn check_definition(f: &PFlow, layer: &Layer, g: &Graph, pplanes: &PPlanes) -> a
             }
         }
         let in_info = (fi.contains(&i), odd_fi.contains(&i));
-        match pi {
-            PPlane::XY if in_info != (false, true) => {
-                let err = anyhow::anyhow!("pplane check failed").context(format!(
-                    "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (false, true): XY"
-                ));
-                return Err(err);
-            }
-            PPlane::YZ if in_info != (true, false) => {
-                let err = anyhow::anyhow!("pplane check failed").context(format!(
-                    "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (true, false): YZ"
-                ));
-                return Err(err);
-            }
-            PPlane::XZ if in_info != (true, true) => {
-                let err = anyhow::anyhow!("pplane check failed").context(format!(
-                    "must satisfy ({i} in f({i}), {i} in Odd(f({i})) = (true, true): XZ"
-                ));
-                return Err(err);
-            }
-            PPlane::X if !in_info.1 => {
-                let err = anyhow::anyhow!("pplane check failed")
-                    .context(format!("{i} must be in Odd(f({i})): X"));
-                return Err(err);
-            }
-            PPlane::Y if !(in_info.0 ^ in_info.1) => {
-                let err = anyhow::anyhow!("pplane check failed").context(format!(
-                    "{i} must be in either f({i}) or Odd(f({i})), not both: Y"
+        if let Some(detail) = pplane_violation(i, pi, in_info) {
+            let err = anyhow::anyhow!("pplane check failed").context(detail);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Runs all the checks on the Pauli flow `(f, layer)`.
+fn verify_impl(
+    f: &PFlow,
+    layer: &Layer,
+    g: &Graph,
+    iset: &Nodes,
+    oset: &Nodes,
+    pplanes: &PPlanes,
+) -> anyhow::Result<()> {
+    validate::check_graph(g, iset, oset)?;
+    let n = g.len();
+    anyhow::ensure!(layer.len() == n, "layer size mismatch");
+    let sets = FlowSets::new(n, iset, oset);
+    let f_flatiter = f
+        .iter()
+        .flat_map(|(i, fi)| Iterator::zip(iter::repeat(i), fi.iter()));
+    validate::check_domain(f_flatiter, &sets)?;
+    validate::check_initial(layer, oset, false)?;
+    check_definition(f, layer, g, pplanes)
+}
+
+/// Verifies the Pauli flow.
+///
+/// # Arguments
+///
+/// - `f`: Pauli flow function.
+/// - `layer`: Layer of each node.
+/// - `tolerant`: Ignore the entries of `pplanes` for the output nodes instead of failing.
+/// - Others: Same as `find`.
+///
+/// # Errors
+///
+/// If `(f, layer)` is not a valid Pauli flow.
+///
+/// # Note
+///
+/// - Even if `tolerant`, each non-output node needs both `f(i)` and `pplanes[i]`.
+#[pyfunction]
+#[pyo3(signature = (f, layer, g, iset, oset, pplanes, tolerant = false))]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn verify(
+    f: PFlow,
+    layer: Layer,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    mut pplanes: PPlanes,
+    tolerant: bool,
+) -> PyResult<()> {
+    if tolerant {
+        pplanes.retain(|u, _| !oset.contains(u));
+    }
+    verify_impl(&f, &layer, &g, &iset, &oset, &pplanes).map_err(utils::to_pyerr)
+}
+
+/// Verifies the Pauli flow and checks if `layer` is maximally delayed.
+///
+/// # Arguments
+///
+/// Same as `verify`.
+///
+/// # Errors
+///
+/// If `(f, layer)` is not a valid Pauli flow or `layer` differs from the one of `find`.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value)]
+pub fn verify_maximal(
+    f: PFlow,
+    layer: Layer,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    pplanes: PPlanes,
+) -> PyResult<()> {
+    let check = || {
+        verify_impl(&f, &layer, &g, &iset, &oset, &pplanes)?;
+        let (_, layer_max) = PFlowWorkspace::default()
+            .search(&g, &iset, &oset, &pplanes, &mut SearchAux::default())
+            .ok_or_else(|| anyhow::anyhow!("maximality check failed: pflow not found"))?;
+        if let Some(u) = (0..g.len()).find(|&u| layer[u] != layer_max[u]) {
+            let err = anyhow::anyhow!("maximality check failed").context(format!(
+                "layer({u}) = {} but {} if maximally delayed",
+                layer[u], layer_max[u]
+            ));
+            return Err(err);
+        }
+        Ok(())
+    };
+    check().map_err(utils::to_pyerr)
+}
+
+/// Checks if the Pauli flow is a faithful embedding of the generalized flow.
+///
+/// # Arguments
+///
+/// - `gflow`: Generalized flow function.
+/// - `pflow`: Pauli flow function embedding `gflow`.
+/// - `planes`: Measurement plane of each node in V\O.
+/// - `pplanes`: Measurement plane of each node in V\O, translated from `planes`.
+/// - Others: Same as `find`.
+///
+/// # Errors
+///
+/// If `pplanes` is not the translation of `planes`, `pflow` differs from `gflow`,
+/// or `pflow` is not a valid Pauli flow under the layer induced by itself.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(
+    clippy::needless_pass_by_value,
+    clippy::too_many_arguments,
+    clippy::similar_names
+)]
+pub fn check_embedding(
+    gflow: PFlow,
+    pflow: PFlow,
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: hashbrown::HashMap<usize, Plane>,
+    pplanes: PPlanes,
+) -> PyResult<()> {
+    let check = || {
+        for (&u, &pu) in &planes {
+            if pplanes.get(&u) != Some(&pu.into()) {
+                let err = anyhow::anyhow!("embedding check failed").context(format!(
+                    "pplane of {u} must be {:?}, got {:?}",
+                    PPlane::from(pu),
+                    pplanes.get(&u)
                 ));
                 return Err(err);
             }
-            PPlane::Z if !in_info.0 => {
-                let err = anyhow::anyhow!("pplane check failed")
-                    .context(format!("{i} must be in f({i}): Z"));
-                return Err(err);
-            }
-            _ => {}
         }
-    }
-    Ok(())
+        anyhow::ensure!(
+            planes.len() == pplanes.len(),
+            "embedding check failed: planes and pplanes must have the same codomain"
+        );
+        if let Some(u) = gflow
+            .keys()
+            .chain(pflow.keys())
+            .find(|&u| gflow.get(u) != pflow.get(u))
+        {
+            let err = anyhow::anyhow!("embedding check failed")
+                .context(format!("correction sets of {u} differ"));
+            return Err(err);
+        }
+        let layer = utils::compute_layer(&pflow, &g)?;
+        verify_impl(&pflow, &layer, &g, &iset, &oset, &pplanes)
+    };
+    check().map_err(utils::to_pyerr)
 }
 
 /// Sellects nodes from `src` with `pred`.
@@ -260,7 +516,7 @@ fn decode_solution<const K: BranchKind>(u: usize, x: &FixedBitSet, colset: &Orde
 
 #[derive(Debug)]
 struct PFlowContext<'a> {
-    work: &'a mut [FixedBitSet],
+    solver: GF2Solver<'a>,
     g: &'a Graph,
     u: usize,
     rowset_upper: &'a OrderedNodes,
@@ -278,19 +534,16 @@ fn find_impl<const K: BranchKind>(ctx: &mut PFlowContext) -> bool {
     };
     let u = ctx.u;
     ctx.x.clear();
-    let ncols = ctx.colset.len();
-    utils::zerofill(ctx.work, ncols + 1);
     init_work::<K>(
-        ctx.work,
+        ctx.solver.reset(),
         u,
         ctx.g,
         ctx.rowset_upper,
         ctx.rowset_lower,
         ctx.colset,
     );
-    let mut solver = GF2Solver::attach(ctx.work, 1);
-    tracing::debug!("{solver:?}");
-    if solver.solve_in_place(ctx.x, 0) {
+    tracing::debug!("{:?}", ctx.solver);
+    if ctx.solver.solve_in_place(ctx.x, 0) {
         tracing::debug!("solution found for {u}");
         ctx.f.insert(u, decode_solution::<K>(u, ctx.x, ctx.colset));
         true
@@ -300,6 +553,55 @@ fn find_impl<const K: BranchKind>(ctx: &mut PFlowContext) -> bool {
     }
 }
 
+/// Branch-specific part of the algorithm, instantiated for each branch kind.
+type BranchFn = fn(&mut PFlowContext) -> bool;
+
+/// Tries the branches allowed by `ppu` until a solution is found.
+///
+/// Returns the branch that succeeded, if any.
+fn find_branches(ctx: &mut PFlowContext, ppu: PPlane, stats: &mut FindStats) -> Option<PPlane> {
+    let branches: [(PPlane, BranchFn, bool); 3] = [
+        (
+            PPlane::XY,
+            find_impl::<BRANCH_XY>,
+            matches!(ppu, PPlane::XY | PPlane::X | PPlane::Y),
+        ),
+        (
+            PPlane::YZ,
+            find_impl::<BRANCH_YZ>,
+            matches!(ppu, PPlane::YZ | PPlane::Y | PPlane::Z),
+        ),
+        (
+            PPlane::XZ,
+            find_impl::<BRANCH_XZ>,
+            matches!(ppu, PPlane::XZ | PPlane::Z | PPlane::X),
+        ),
+    ];
+    for (bu, find_bu, allowed) in branches {
+        if !allowed {
+            continue;
+        }
+        tracing::debug!("==={bu:?} branch===");
+        *stats.branch_attempts.entry(bu).or_default() += 1;
+        let found = find_bu(ctx);
+        stats.record_solver(&ctx.solver);
+        if found {
+            *stats.branch_successes.entry(bu).or_default() += 1;
+            return Some(bu);
+        }
+    }
+    None
+}
+
+/// Auxiliary outputs of `PFlowWorkspace::search`.
+#[derive(Debug, Default)]
+struct SearchAux {
+    /// Branch used for each corrected node.
+    branch: PPlanes,
+    /// Solver and branch statistics.
+    stats: FindStats,
+}
+
 /// Finds the maximally-delayed Pauli flow.
 ///
 /// # Arguments
@@ -321,104 +623,258 @@ fn find_impl<const K: BranchKind>(ctx: &mut PFlowContext) -> bool {
 #[tracing::instrument]
 #[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
 pub fn find(g: Graph, iset: Nodes, oset: Nodes, pplanes: PPlanes) -> Option<(PFlow, Layer)> {
-    validate::check_graph(&g, &iset, &oset).unwrap();
-    let yset = matching_nodes(&pplanes, |pp| matches!(pp, PPlane::Y));
-    let xyset = matching_nodes(&pplanes, |pp| matches!(pp, PPlane::X | PPlane::Y));
-    let yzset = matching_nodes(&pplanes, |pp| matches!(pp, PPlane::Y | PPlane::Z));
-    let n = g.len();
-    let vset = (0..n).collect::<Nodes>();
-    let mut cset = Nodes::new();
-    let mut ocset = vset.difference(&oset).copied().collect::<Nodes>();
-    let mut rowset_upper = vset.difference(&yzset).copied().collect::<OrderedNodes>();
-    let mut rowset_lower = yset.iter().copied().collect::<OrderedNodes>();
-    let mut colset = xyset.difference(&iset).copied().collect::<OrderedNodes>();
-    let mut f = PFlow::with_capacity(ocset.len());
-    let mut layer = vec![0_usize; n];
-    let mut work = vec![FixedBitSet::new(); rowset_upper.len() + rowset_lower.len()];
-    for l in 0_usize.. {
-        tracing::debug!("=====layer {l}=====");
-        cset.clear();
-        for &u in &ocset {
-            let rowset_upper = ScopedInclude::new(&mut rowset_upper, u);
-            let rowset_lower = ScopedExclude::new(&mut rowset_lower, u);
-            let colset = ScopedExclude::new(&mut colset, u);
-            let nrows_upper = rowset_upper.len();
-            let nrows_lower = rowset_lower.len();
-            let ncols = colset.len();
-            if nrows_upper + nrows_lower == 0 || ncols == 0 {
-                continue;
-            }
-            let ppu = pplanes[&u];
-            tracing::debug!("====checking {u} ({ppu:?})====");
-            tracing::debug!("rowset_upper: {:?}", &*rowset_upper);
-            tracing::debug!("rowset_lower: {:?}", &*rowset_lower);
-            tracing::debug!("colset      : {:?}", &*colset);
-            // No monotonicity guarantees
-            work.resize_with(nrows_upper + nrows_lower, || {
-                FixedBitSet::with_capacity(ncols + 1)
-            });
-            let mut x = FixedBitSet::with_capacity(ncols);
-            let mut done = false;
-            let mut ctx = PFlowContext {
-                work: &mut work,
-                g: &g,
-                u,
-                rowset_upper: &rowset_upper,
-                rowset_lower: &rowset_lower,
-                colset: &colset,
-                x: &mut x,
-                f: &mut f,
-            };
-            if !done && matches!(ppu, PPlane::XY | PPlane::X | PPlane::Y) {
-                tracing::debug!("===XY branch===");
-                done |= find_impl::<BRANCH_XY>(&mut ctx);
-            }
-            if !done && matches!(ppu, PPlane::YZ | PPlane::Y | PPlane::Z) {
-                tracing::debug!("===YZ branch===");
-                done |= find_impl::<BRANCH_YZ>(&mut ctx);
-            }
-            if !done && matches!(ppu, PPlane::XZ | PPlane::Z | PPlane::X) {
-                tracing::debug!("===XZ branch===");
-                done |= find_impl::<BRANCH_XZ>(&mut ctx);
+    PFlowWorkspace::default().search(&g, &iset, &oset, &pplanes, &mut SearchAux::default())
+}
+
+/// Finds the maximally-delayed Pauli flow and bundles it with the derived data.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Note
+///
+/// - `depth` is the number of layers.
+/// - `branch` tells which of the `XY`, `YZ`, and `XZ` systems was solved for each node.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_verbose(g: Graph, iset: Nodes, oset: Nodes, pplanes: PPlanes) -> Option<PFlowResult> {
+    let mut aux = SearchAux::default();
+    let (f, layer) = PFlowWorkspace::default().search(&g, &iset, &oset, &pplanes, &mut aux)?;
+    let depth = utils::depth(&layer);
+    Some(PFlowResult {
+        f,
+        layer,
+        depth,
+        branch: aux.branch,
+    })
+}
+
+/// Same as `find`, but also reports the solver and branch statistics.
+///
+/// # Arguments
+///
+/// Same as `find`.
+///
+/// # Returns
+///
+/// Result of `find` and the statistics, collected even if Pauli flow is not found.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - Each node tries the branches allowed by its measurement in the order of `XY`, `YZ`, and `XZ`, once per layer until corrected.
+/// - `branch_attempts[b] - branch_successes[b]` is the number of times the branch `b` fell through.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(clippy::needless_pass_by_value, clippy::must_use_candidate)]
+pub fn find_with_stats(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    pplanes: PPlanes,
+) -> (Option<(PFlow, Layer)>, FindStats) {
+    let mut aux = SearchAux::default();
+    let ret = PFlowWorkspace::default().search(&g, &iset, &oset, &pplanes, &mut aux);
+    (ret, aux.stats)
+}
+
+/// Finds the maximally-delayed Pauli flow from the measurement planes and angles.
+///
+/// # Arguments
+///
+/// - `planes`: Measurement plane of each node in V\O.
+/// - `angles`: Measurement angle of each node in V\O, in units of pi.
+/// - `atol`: Absolute tolerance to detect Pauli measurements.
+/// - Others: Same as `find`.
+///
+/// # Panics
+///
+/// If inputs/outputs do not pass the validation.
+///
+/// # Note
+///
+/// - `PPlane` of each node is classified by `PPlane::from_plane_angle`.
+/// - Nodes missing in `angles` are treated as non-Pauli.
+#[pyfunction]
+#[tracing::instrument]
+#[allow(
+    clippy::needless_pass_by_value,
+    clippy::must_use_candidate,
+    clippy::similar_names
+)]
+pub fn find_from_angles(
+    g: Graph,
+    iset: Nodes,
+    oset: Nodes,
+    planes: hashbrown::HashMap<usize, Plane>,
+    angles: hashbrown::HashMap<usize, f64>,
+    atol: f64,
+) -> Option<(PFlow, Layer)> {
+    let pplanes = planes
+        .iter()
+        .map(|(&u, &pu)| {
+            let ppu = angles
+                .get(&u)
+                .map_or(pu.into(), |&a| PPlane::from_plane_angle(pu, a, atol));
+            (u, ppu)
+        })
+        .collect::<PPlanes>();
+    tracing::debug!("pplanes: {pplanes:?}");
+    find(g, iset, oset, pplanes)
+}
+
+#[pyclass]
+#[derive(Debug, Default)]
+/// Working storage of `find` reusable across calls.
+///
+/// Useful to avoid reallocation when searching for Pauli flow repeatedly on similar graphs.
+pub struct PFlowWorkspace {
+    work: Vec<FixedBitSet>,
+    x: FixedBitSet,
+    rowset_upper: OrderedNodes,
+    rowset_lower: OrderedNodes,
+    colset: OrderedNodes,
+}
+
+#[pymethods]
+impl PFlowWorkspace {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Same as `find`, but reuses the buffers of `self`.
+    ///
+    /// # Panics
+    ///
+    /// If inputs/outputs do not pass the validation.
+    #[tracing::instrument(skip(self))]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn find_with(
+        &mut self,
+        g: Graph,
+        iset: Nodes,
+        oset: Nodes,
+        pplanes: PPlanes,
+    ) -> Option<(PFlow, Layer)> {
+        self.search(&g, &iset, &oset, &pplanes, &mut SearchAux::default())
+    }
+}
+
+impl PFlowWorkspace {
+    /// Implements `find` on top of the buffers of `self`.
+    ///
+    /// The branch used for each node and the statistics are written to `aux`.
+    fn search(
+        &mut self,
+        g: &Graph,
+        iset: &Nodes,
+        oset: &Nodes,
+        pplanes: &PPlanes,
+        aux: &mut SearchAux,
+    ) -> Option<(PFlow, Layer)> {
+        validate::check_graph(g, iset, oset).unwrap();
+        let Self {
+            work,
+            x,
+            rowset_upper,
+            rowset_lower,
+            colset,
+        } = self;
+        let yset = matching_nodes(pplanes, |pp| matches!(pp, PPlane::Y));
+        let xyset = matching_nodes(pplanes, |pp| matches!(pp, PPlane::X | PPlane::Y));
+        let yzset = matching_nodes(pplanes, |pp| matches!(pp, PPlane::Y | PPlane::Z));
+        let n = g.len();
+        let sets = FlowSets::new(n, iset, oset);
+        let mut cset = Nodes::new();
+        let mut ocset = sets.ocset.clone();
+        rowset_upper.clear();
+        rowset_upper.extend(sets.vset.difference(&yzset));
+        rowset_lower.clear();
+        rowset_lower.extend(&yset);
+        colset.clear();
+        colset.extend(xyset.difference(iset));
+        let mut f = PFlow::with_capacity(ocset.len());
+        let mut layer = vec![0_usize; n];
+        for l in 0_usize.. {
+            tracing::debug!("=====layer {l}=====");
+            cset.clear();
+            for &u in &ocset {
+                let rowset_upper = ScopedInclude::new(rowset_upper, u);
+                let rowset_lower = ScopedExclude::new(rowset_lower, u);
+                let colset = ScopedExclude::new(colset, u);
+                let nrows_upper = rowset_upper.len();
+                let nrows_lower = rowset_lower.len();
+                let ncols = colset.len();
+                if nrows_upper + nrows_lower == 0 || ncols == 0 {
+                    continue;
+                }
+                let ppu = pplanes[&u];
+                tracing::debug!("====checking {u} ({ppu:?})====");
+                tracing::debug!("rowset_upper: {:?}", &*rowset_upper);
+                tracing::debug!("rowset_lower: {:?}", &*rowset_lower);
+                tracing::debug!("colset      : {:?}", &*colset);
+                // No monotonicity guarantees
+                work.resize_with(nrows_upper + nrows_lower, || {
+                    FixedBitSet::with_capacity(ncols + 1)
+                });
+                utils::zerofill(work, ncols + 1);
+                utils::zerofill(slice::from_mut(x), ncols);
+                let mut ctx = PFlowContext {
+                    solver: GF2Solver::attach(work, 1),
+                    g,
+                    u,
+                    rowset_upper: &rowset_upper,
+                    rowset_lower: &rowset_lower,
+                    colset: &colset,
+                    x,
+                    f: &mut f,
+                };
+                if let Some(bu) = find_branches(&mut ctx, ppu, &mut aux.stats) {
+                    tracing::debug!("f({}) = {:?}", u, &f[&u]);
+                    aux.branch.insert(u, bu);
+                    tracing::debug!("layer({u}) = {l}");
+                    layer[u] = l;
+                    cset.insert(u);
+                } else {
+                    tracing::debug!("solution not found: {u} (all branches)");
+                }
             }
-            if done {
-                tracing::debug!("f({}) = {:?}", u, &f[&u]);
-                tracing::debug!("layer({u}) = {l}");
-                layer[u] = l;
-                cset.insert(u);
-            } else {
-                tracing::debug!("solution not found: {u} (all branches)");
+            if l == 0 {
+                rowset_upper.difference_with(oset);
+                rowset_lower.difference_with(oset);
+                colset.extend(oset.difference(iset));
+            } else if cset.is_empty() {
+                break;
             }
+            ocset.difference_with(&cset);
+            rowset_upper.difference_with(&cset);
+            rowset_lower.difference_with(&cset);
+            colset.extend(cset.difference(iset));
         }
-        if l == 0 {
-            rowset_upper.difference_with(&oset);
-            rowset_lower.difference_with(&oset);
-            colset.extend(oset.difference(&iset));
-        } else if cset.is_empty() {
-            break;
+        if ocset.is_empty() {
+            tracing::debug!("pflow found");
+            tracing::debug!("pflow: {f:?}");
+            tracing::debug!("layer: {layer:?}");
+            // TODO: Uncomment once ready
+            // if cfg!(debug_assertions) {
+            let f_flatiter = f
+                .iter()
+                .flat_map(|(i, fi)| Iterator::zip(iter::repeat(i), fi.iter()));
+            validate::check_domain(f_flatiter, &sets).unwrap();
+            validate::check_initial(&layer, oset, false).unwrap();
+            check_definition(&f, &layer, g, pplanes).unwrap();
+            // }
+            Some((f, layer))
+        } else {
+            tracing::debug!("pflow not found");
+            None
         }
-        ocset.difference_with(&cset);
-        rowset_upper.difference_with(&cset);
-        rowset_lower.difference_with(&cset);
-        colset.extend(cset.difference(&iset));
-    }
-    if ocset.is_empty() {
-        tracing::debug!("pflow found");
-        tracing::debug!("pflow: {f:?}");
-        tracing::debug!("layer: {layer:?}");
-        // TODO: Uncomment once ready
-        // if cfg!(debug_assertions) {
-        let f_flatiter = f
-            .iter()
-            .flat_map(|(i, fi)| Iterator::zip(iter::repeat(i), fi.iter()));
-        validate::check_domain(f_flatiter, &vset, &iset, &oset).unwrap();
-        validate::check_initial(&layer, &oset, false).unwrap();
-        check_definition(&f, &layer, &g, &pplanes).unwrap();
-        // }
-        Some((f, layer))
-    } else {
-        tracing::debug!("pflow not found");
-        None
     }
 }
 
@@ -429,6 +885,110 @@ mod tests {
     use super::*;
     use crate::internal::test_utils::{self, TestCase};
 
+    /// Checks the definition of Pauli flow in Simmons, arXiv:2109.05654, independently of `check_definition`.
+    ///
+    /// `i` precedes `j` iff `layer[i] > layer[j]`, and outputs are treated as non-Pauli.
+    /// Simmons requires `f(i)` to be in `V\I`, whereas this crate also allows an input `i` in `f(i)` (see `check_domain`).
+    /// The convention of this crate is followed iff `self_inputs`.
+    fn check_reference(
+        f: &PFlow,
+        layer: &Layer,
+        TestCase { g, iset, oset }: &TestCase,
+        pplanes: &PPlanes,
+        self_inputs: bool,
+    ) -> bool {
+        let n = g.len();
+        let odd_contains =
+            |fi: &Nodes, j: usize| g[j].iter().filter(|&k| fi.contains(k)).count() % 2 == 1;
+        let domain_ok = (0..n).all(|i| f.contains_key(&i) != oset.contains(&i))
+            && f.iter().all(|(i, fi)| {
+                fi.iter()
+                    .all(|j| (self_inputs && j == i) || !iset.contains(j))
+            });
+        domain_ok
+            && f.iter().all(|(&i, fi)| {
+                let pauli =
+                    |j: usize, ps: &[PPlane]| pplanes.get(&j).is_some_and(|pj| ps.contains(pj));
+                let others_ok = (0..n).filter(|&j| j != i).all(|j| {
+                    let (in_f, in_odd) = (fi.contains(&j), odd_contains(fi, j));
+                    let precedes = layer[i] > layer[j];
+                    // P1
+                    (!in_f || pauli(j, &[PPlane::X, PPlane::Y]) || precedes)
+                        // P2
+                        && (precedes || pauli(j, &[PPlane::Y, PPlane::Z]) || !in_odd)
+                        // P3
+                        && (precedes || !pauli(j, &[PPlane::Y]) || in_f == in_odd)
+                });
+                let (in_f, in_odd) = (fi.contains(&i), odd_contains(fi, i));
+                // P4-P9
+                let self_ok = match pplanes[&i] {
+                    PPlane::XY => !in_f && in_odd,
+                    PPlane::XZ => in_f && in_odd,
+                    PPlane::YZ => in_f && !in_odd,
+                    PPlane::X => in_odd,
+                    PPlane::Y => in_f != in_odd,
+                    PPlane::Z => in_f,
+                };
+                others_ok && self_ok
+            })
+    }
+
+    /// Checks `find` and the alternative `f` against the reference, and their perturbations against `verify`.
+    fn check_reference_with(case: &TestCase, pplanes: &PPlanes, alt: (usize, Nodes)) {
+        let TestCase { g, iset, oset } = case.clone();
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), pplanes.clone()).unwrap();
+        let mut f_alt = f.clone();
+        f_alt.insert(alt.0, alt.1);
+        for f in [&f, &f_alt] {
+            assert!(check_reference(f, &layer, case, pplanes, true));
+            // The two conventions differ only in inputs correcting themselves
+            let self_input = f.iter().any(|(i, fi)| iset.contains(i) && fi.contains(i));
+            assert_eq!(
+                check_reference(f, &layer, case, pplanes, false),
+                !self_input
+            );
+        }
+        for (i, j) in itertools::iproduct!(f.keys().copied(), 0..g.len()) {
+            let mut f_ = f.clone();
+            let fi = f_.get_mut(&i).unwrap();
+            if !fi.remove(&j) {
+                fi.insert(j);
+            }
+            let expected = check_reference(&f_, &layer, case, pplanes, true);
+            let actual = verify(
+                f_,
+                layer.clone(),
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                pplanes.clone(),
+                false,
+            );
+            assert_eq!(actual.is_ok(), expected, "f({i}) toggled at {j}");
+        }
+    }
+
+    #[test]
+    fn test_reference_case7() {
+        let pplanes = measurements! {
+            0: PPlane::Z,
+            1: PPlane::Z,
+            2: PPlane::Y,
+            3: PPlane::Y
+        };
+        check_reference_with(&test_utils::CASE7, &pplanes, (0, Nodes::from([0, 1])));
+    }
+
+    #[test]
+    fn test_reference_case8() {
+        let pplanes = measurements! {
+            0: PPlane::Z,
+            1: PPlane::XZ,
+            2: PPlane::Y
+        };
+        check_reference_with(&test_utils::CASE8, &pplanes, (0, Nodes::from([0, 3, 4])));
+    }
+
     #[test_log::test]
     fn test_find_case0() {
         let TestCase { g, iset, oset } = test_utils::CASE0.clone();
@@ -554,8 +1114,7 @@ mod tests {
         let flen = g.len() - oset.len();
         let (f, layer) = find(g, iset, oset, pplanes).unwrap();
         assert_eq!(f.len(), flen);
-        // Graphix
-        // assert_eq!(f[&0], Nodes::from([0, 1]));
+        // Graphix returns f(0) = {0, 1}, also valid: see test_reference_case7
         assert_eq!(f[&0], Nodes::from([0]));
         assert_eq!(f[&1], Nodes::from([1]));
         assert_eq!(f[&2], Nodes::from([2]));
@@ -563,6 +1122,34 @@ mod tests {
         assert_eq!(layer, vec![1, 0, 0, 1, 0]);
     }
 
+    #[test_log::test]
+    fn test_find_with_reuse() {
+        let case6 = test_utils::CASE6.clone();
+        let pplanes6 = measurements! {
+            0: PPlane::XY,
+            1: PPlane::X,
+            2: PPlane::XY,
+            3: PPlane::X
+        };
+        let case7 = test_utils::CASE7.clone();
+        let pplanes7 = measurements! {
+            0: PPlane::Z,
+            1: PPlane::Z,
+            2: PPlane::Y,
+            3: PPlane::Y
+        };
+        let mut ws = PFlowWorkspace::default();
+        for (TestCase { g, iset, oset }, pplanes) in [
+            (&case6, &pplanes6),
+            (&case7, &pplanes7),
+            (&case6, &pplanes6),
+        ] {
+            let expected = find(g.clone(), iset.clone(), oset.clone(), pplanes.clone());
+            let actual = ws.find_with(g.clone(), iset.clone(), oset.clone(), pplanes.clone());
+            assert_eq!(actual, expected);
+        }
+    }
+
     #[test_log::test]
     fn test_find_case8() {
         let TestCase { g, iset, oset } = test_utils::CASE8.clone();
@@ -574,11 +1161,344 @@ mod tests {
         let flen = g.len() - oset.len();
         let (f, layer) = find(g, iset, oset, pplanes).unwrap();
         assert_eq!(f.len(), flen);
-        // Graphix
-        // assert_eq!(f[&0], Nodes::from([0, 3, 4]));
+        // Graphix returns f(0) = {0, 3, 4}, also valid: see test_reference_case8
         assert_eq!(f[&0], Nodes::from([0, 2, 4]));
         assert_eq!(f[&1], Nodes::from([1, 2]));
         assert_eq!(f[&2], Nodes::from([4]));
         assert_eq!(layer, vec![1, 1, 1, 0, 0]);
     }
+
+    #[test_log::test]
+    fn test_verify_tolerant() {
+        let TestCase { g, iset, oset } = test_utils::CASE6.clone();
+        let mut pplanes = measurements! {
+            0: PPlane::XY,
+            1: PPlane::X,
+            2: PPlane::XY,
+            3: PPlane::X
+        };
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), pplanes.clone()).unwrap();
+        let check = |pplanes: &PPlanes, tolerant| {
+            let (f, layer, g, iset, oset) = (&f, &layer, &g, &iset, &oset);
+            verify(
+                f.clone(),
+                layer.clone(),
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                pplanes.clone(),
+                tolerant,
+            )
+        };
+        // 4 in O, which must not act as a Pauli node
+        pplanes.insert(4, PPlane::X);
+        assert!(check(&pplanes, false).is_err());
+        check(&pplanes, true).unwrap();
+        // Still required for V\O
+        pplanes.remove(&3);
+        assert!(check(&pplanes, true).is_err());
+    }
+
+    #[test_log::test]
+    fn test_verify_maximal() {
+        let TestCase { g, iset, oset } = test_utils::CASE6.clone();
+        let pplanes = measurements! {
+            0: PPlane::XY,
+            1: PPlane::X,
+            2: PPlane::XY,
+            3: PPlane::X
+        };
+        let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), pplanes.clone()).unwrap();
+        let args = (g, iset, oset, pplanes);
+        let verify_strict =
+            |f, layer, g, iset, oset, pplanes| verify(f, layer, g, iset, oset, pplanes, false);
+        let verify_with =
+            |layer: &Layer,
+             check: fn(PFlow, Layer, Graph, Nodes, Nodes, PPlanes) -> PyResult<()>| {
+                let (g, iset, oset, pplanes) = args.clone();
+                check(f.clone(), layer.clone(), g, iset, oset, pplanes)
+            };
+        verify_with(&layer, verify_strict).unwrap();
+        verify_with(&layer, verify_maximal).unwrap();
+        // Still valid, but too eager
+        let layer_eager = vec![2, 2, 1, 2, 0];
+        verify_with(&layer_eager, verify_strict).unwrap();
+        assert!(verify_with(&layer_eager, verify_maximal).is_err());
+        // Invalid: 4 in O
+        let layer_invalid = vec![1, 1, 0, 1, 1];
+        assert!(verify_with(&layer_invalid, verify_strict).is_err());
+        assert!(verify_with(&layer_invalid, verify_maximal).is_err());
+    }
+
+    #[test_log::test]
+    fn test_check_definition_ng() {
+        let TestCase { g, .. } = test_utils::CASE6.clone();
+        // Avoid layer check failures
+        let layer = vec![0, 1, 0, 1, 0];
+        let check = |f: PFlow, pplanes: PPlanes| {
+            format!(
+                "{:#}",
+                check_definition(&f, &layer, &g, &pplanes).unwrap_err()
+            )
+        };
+        // 1 in Odd({4}), but not in {4}
+        let f = PFlow::from([(1, Nodes::from([4]))]);
+        let msg = check(f.clone(), measurements! { 1: PPlane::YZ });
+        assert!(msg.contains("1 not in f(1), but YZ requires 1 in f(1)"));
+        let msg = check(f.clone(), measurements! { 1: PPlane::Z });
+        assert!(msg.contains("1 not in f(1), but Z requires 1 in f(1)"));
+        // 1 in {1, 4}, and in Odd({1, 4}) = {0, 1, 2, 4}
+        let f = PFlow::from([(1, Nodes::from([1, 4]))]);
+        let msg = check(f.clone(), measurements! { 1: PPlane::XY });
+        assert!(msg.contains("1 in f(1), but XY requires 1 not in f(1)"));
+        let msg = check(f.clone(), measurements! { 1: PPlane::YZ });
+        assert!(msg.contains("1 in Odd(f(1)), but YZ requires 1 not in Odd(f(1))"));
+        let msg = check(f.clone(), measurements! { 1: PPlane::Y });
+        assert!(msg.contains("1 in both f(1) and Odd(f(1)), but Y requires exactly one"));
+        // 3 in {3}, but not in Odd({3}) = {2}
+        let f = PFlow::from([(3, Nodes::from([3]))]);
+        let msg = check(f.clone(), measurements! { 3: PPlane::X });
+        assert!(msg.contains("3 not in Odd(f(3)), but X requires 3 in Odd(f(3))"));
+        let msg = check(f.clone(), measurements! { 3: PPlane::XZ });
+        assert!(msg.contains("3 not in Odd(f(3)), but XZ requires 3 in Odd(f(3))"));
+    }
+
+    #[test_log::test]
+    fn test_find_verbose() {
+        let cases = [
+            (
+                test_utils::CASE7.clone(),
+                measurements! {
+                    0: PPlane::Z,
+                    1: PPlane::Z,
+                    2: PPlane::Y,
+                    3: PPlane::Y
+                },
+            ),
+            (
+                test_utils::CASE8.clone(),
+                measurements! {
+                    0: PPlane::Z,
+                    1: PPlane::XZ,
+                    2: PPlane::Y
+                },
+            ),
+        ];
+        for (TestCase { g, iset, oset }, pplanes) in cases {
+            let (f, layer) = find(g.clone(), iset.clone(), oset.clone(), pplanes.clone()).unwrap();
+            let res = find_verbose(g, iset, oset, pplanes.clone()).unwrap();
+            assert_eq!(res.f, f);
+            assert_eq!(res.depth, utils::depth(&layer));
+            assert_eq!(res.layer, layer);
+            assert_eq!(res.branch.len(), f.len());
+            for (i, fi) in &f {
+                // Only the XY branch excludes i
+                assert_eq!(res.branch[i] == PPlane::XY, !fi.contains(i));
+                match pplanes[i] {
+                    PPlane::X => assert_ne!(res.branch[i], PPlane::YZ),
+                    PPlane::Y => assert_ne!(res.branch[i], PPlane::XZ),
+                    PPlane::Z => assert_ne!(res.branch[i], PPlane::XY),
+                    pi => assert_eq!(res.branch[i], pi),
+                }
+            }
+        }
+    }
+
+    #[test_log::test]
+    fn test_find_with_stats() {
+        let cases = [
+            (
+                test_utils::CASE6.clone(),
+                measurements! {
+                    0: PPlane::XY,
+                    1: PPlane::X,
+                    2: PPlane::XY,
+                    3: PPlane::X
+                },
+                // X nodes fall through to XZ twice in total, never succeeding there
+                [(PPlane::XY, 7), (PPlane::XZ, 2)],
+            ),
+            (
+                test_utils::CASE7.clone(),
+                measurements! {
+                    0: PPlane::Z,
+                    1: PPlane::Z,
+                    2: PPlane::Y,
+                    3: PPlane::Y
+                },
+                [(PPlane::XY, 3), (PPlane::YZ, 5)],
+            ),
+            (
+                test_utils::CASE8.clone(),
+                measurements! {
+                    0: PPlane::Z,
+                    1: PPlane::XZ,
+                    2: PPlane::Y
+                },
+                [(PPlane::XY, 1), (PPlane::YZ, 2)],
+            ),
+        ];
+        for (TestCase { g, iset, oset }, pplanes, attempts) in cases {
+            let res = find_verbose(g.clone(), iset.clone(), oset.clone(), pplanes.clone()).unwrap();
+            let (ret, stats) = find_with_stats(g, iset, oset, pplanes);
+            assert_eq!(ret, Some((res.f.clone(), res.layer.clone())));
+            // One success per node, in the branch reported by find_verbose
+            let mut successes = hashbrown::HashMap::<PPlane, usize>::new();
+            for &bu in res.branch.values() {
+                *successes.entry(bu).or_default() += 1;
+            }
+            assert_eq!(stats.branch_successes, successes);
+            for (bu, &k) in &stats.branch_attempts {
+                assert!(k >= stats.branch_successes.get(bu).copied().unwrap_or_default());
+            }
+            for (bu, k) in attempts {
+                assert_eq!(stats.branch_attempts[&bu], k, "{bu:?}");
+            }
+            assert!(stats.total_ones > 0);
+        }
+    }
+
+    #[test]
+    fn test_pflow_result_pickle() {
+        let TestCase { g, iset, oset } = test_utils::CASE8.clone();
+        let pplanes = measurements! {
+            0: PPlane::Z,
+            1: PPlane::XZ,
+            2: PPlane::Y
+        };
+        let res = find_verbose(g, iset, oset, pplanes).unwrap();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            // Stand-in for the extension module
+            let modules = py.import_bound("sys").unwrap().getattr("modules").unwrap();
+            for name in ["fastflow", "fastflow._impl", "fastflow._impl.pflow"] {
+                let m = PyModule::new_bound(py, name).unwrap();
+                m.add_class::<PPlane>().unwrap();
+                m.add_class::<PFlowResult>().unwrap();
+                modules.set_item(name, m).unwrap();
+            }
+            let pickle = py.import_bound("pickle").unwrap();
+            let dumped = pickle
+                .call_method1("dumps", (res.clone().into_py(py),))
+                .unwrap();
+            let loaded = pickle.call_method1("loads", (dumped,)).unwrap();
+            assert_eq!(loaded.extract::<PFlowResult>().unwrap(), res);
+        });
+    }
+
+    #[test_log::test]
+    #[allow(clippy::similar_names)]
+    fn test_check_embedding() {
+        let TestCase { g, iset, oset } = test_utils::CASE4.clone();
+        let planes = hashbrown::HashMap::from([
+            (0, Plane::XY),
+            (1, Plane::XY),
+            (2, Plane::XZ),
+            (3, Plane::YZ),
+        ]);
+        let (gflow, _) =
+            crate::gflow::find(g.clone(), iset.clone(), oset.clone(), planes.clone()).unwrap();
+        let pplanes = planes
+            .iter()
+            .map(|(&u, &pu)| (u, pu.into()))
+            .collect::<PPlanes>();
+        let args = (g, iset, oset, planes);
+        let check = |gflow, pflow, pplanes| {
+            let (g, iset, oset, planes) = args.clone();
+            check_embedding(gflow, pflow, g, iset, oset, planes, pplanes)
+        };
+        check(gflow.clone(), gflow.clone(), pplanes.clone()).unwrap();
+        // Wrong translation
+        let mut pplanes_ng = pplanes.clone();
+        pplanes_ng.insert(2, PPlane::Z);
+        assert!(check(gflow.clone(), gflow.clone(), pplanes_ng).is_err());
+        // Diverged corrections
+        let mut pflow = gflow.clone();
+        pflow.get_mut(&3).unwrap().insert(4);
+        assert!(check(gflow.clone(), pflow, pplanes.clone()).is_err());
+        // Consistent, but invalid
+        let mut f_ng = gflow;
+        f_ng.insert(0, Nodes::from([1]));
+        assert!(check(f_ng.clone(), f_ng, pplanes).is_err());
+    }
+
+    #[test]
+    fn test_from_plane_angle() {
+        let atol = 1e-9;
+        let cases = [
+            (Plane::XY, [PPlane::X, PPlane::Y]),
+            (Plane::YZ, [PPlane::Z, PPlane::Y]),
+            (Plane::XZ, [PPlane::Z, PPlane::X]),
+        ];
+        for (plane, [even, odd]) in cases {
+            for k in -4..4 {
+                let angle = f64::from(k) / 2.0;
+                let expected = if k % 2 == 0 { even } else { odd };
+                assert_eq!(PPlane::from_plane_angle(plane, angle, atol), expected);
+                assert_eq!(
+                    PPlane::from_plane_angle(plane, angle + 1e-12, atol),
+                    expected
+                );
+            }
+            assert_eq!(PPlane::from_plane_angle(plane, 0.25, atol), plane.into());
+            assert_eq!(
+                PPlane::from_plane_angle(plane, 0.5 + 1e-6, atol),
+                plane.into()
+            );
+            // Non-finite inputs
+            for angle in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+                assert_eq!(PPlane::from_plane_angle(plane, angle, atol), plane.into());
+            }
+            for angle in [0.0, 0.25, 0.5] {
+                assert_eq!(
+                    PPlane::from_plane_angle(plane, angle, f64::NAN),
+                    plane.into()
+                );
+            }
+        }
+    }
+
+    #[test_log::test]
+    #[allow(clippy::similar_names)]
+    fn test_find_from_angles() {
+        let TestCase { g, iset, oset } = test_utils::CASE7.clone();
+        let planes = hashbrown::HashMap::from([
+            (0, Plane::XZ),
+            (1, Plane::YZ),
+            (2, Plane::XY),
+            (3, Plane::YZ),
+        ]);
+        let atol = 1e-9;
+        let find_with = |angles: &[(usize, f64)]| {
+            let angles = angles.iter().copied().collect();
+            find_from_angles(
+                g.clone(),
+                iset.clone(),
+                oset.clone(),
+                planes.clone(),
+                angles,
+                atol,
+            )
+        };
+        // Generic: same as gflow
+        let generic = find_with(&[(0, 0.1), (1, 0.2), (2, 0.3), (3, 0.4)]);
+        let gflow = crate::gflow::find(g.clone(), iset.clone(), oset.clone(), planes.clone());
+        assert_eq!(generic.is_some(), gflow.is_some());
+        assert!(generic.is_none());
+        // Pauli: Z, Z, Y, Y as in test_find_case7
+        let (f, layer) = find_with(&[(0, 0.0), (1, 1.0), (2, 0.5), (3, -0.5)]).unwrap();
+        let pplanes = measurements! {
+            0: PPlane::Z,
+            1: PPlane::Z,
+            2: PPlane::Y,
+            3: PPlane::Y
+        };
+        assert_eq!(
+            layer,
+            find(g.clone(), iset.clone(), oset.clone(), pplanes.clone())
+                .unwrap()
+                .1
+        );
+        verify(f, layer, g, iset, oset, pplanes, false).unwrap();
+    }
 }
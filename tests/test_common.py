@@ -0,0 +1,158 @@
+"""Test common."""
+
+import random
+
+import pytest
+from fastflow._impl.common import (
+    GF2System,
+    det_gf2,
+    invert,
+    nullity,
+    solve_all_solutions,
+    solve_int,
+    solve_one,
+    solve_sparse,
+    solve_with_rank,
+)
+
+
+def _lhs(a: list[list[bool]], x: list[bool]) -> list[bool]:
+    return [sum(aij and xj for aij, xj in zip(ai, x)) % 2 == 1 for ai in a]
+
+
+def test_gf2_system_reuse() -> None:
+    """Solve many right-hand sides against one matrix."""
+    a = [[True, True, False], [False, True, True], [True, False, True]]
+    sys = GF2System(a)
+    b0 = [True, False, True]
+    assert sys.add_rhs(b0) == 0
+    x0 = sys.solve(0)
+    assert x0 is not None
+    assert _lhs(a, x0) == b0
+    # Added after elimination
+    assert sys.add_rhs([True, True, True]) == 1
+    assert sys.solve(1) is None
+    b2 = [False, True, True]
+    assert sys.add_rhs(b2) == 2  # noqa: PLR2004
+    x2 = sys.solve(2)
+    assert x2 is not None
+    assert _lhs(a, x2) == b2
+    assert sys.solve(0) == x0
+    assert sys.neqs == 3  # noqa: PLR2004
+
+
+def test_gf2_system_invalid() -> None:
+    """Reject malformed inputs."""
+    with pytest.raises(ValueError, match="rectangular"):
+        GF2System([[True], [True, False]])
+    sys = GF2System([[True]])
+    with pytest.raises(ValueError, match="size mismatch"):
+        sys.add_rhs([True, False])
+    with pytest.raises(ValueError, match="out of range"):
+        sys.solve(0)
+
+
+def test_solve_with_rank() -> None:
+    """Report the shared rank with each solution."""
+    a = [[True, True, False], [False, True, True], [True, False, True]]
+    (x0, r0), (x1, r1) = solve_with_rank(a, [[True, False, True], [True, True, True]])
+    assert x0 is not None
+    assert _lhs(a, x0) == [True, False, True]
+    assert x1 is None
+    assert r0 == r1 == 2  # noqa: PLR2004
+    eye = [[True, False], [False, True]]
+    assert solve_with_rank(eye, [[False, True]]) == [([False, True], 2)]
+
+
+@pytest.mark.parametrize("b", [[True, False, True], [True, True, True]])
+def test_solve_one(b: list[bool]) -> None:
+    """Compare with the batched version."""
+    a = [[True, True, False], [False, True, True], [True, False, True]]
+    ((expected, _),) = solve_with_rank(a, [b])
+    assert solve_one(a, b) == expected
+
+
+@pytest.mark.parametrize(
+    ("a", "expected"),
+    [
+        ([[True, True, False], [False, True, True], [True, False, True]], 1),
+        ([[True, False], [False, True]], 0),
+        ([[False] * 4] * 2, 4),
+    ],
+)
+def test_nullity(a: list[list[bool]], expected: int) -> None:
+    """Check the kernel dimension of known matrices."""
+    assert nullity(a) == expected
+
+
+def test_solve_sparse() -> None:
+    """Compare the CSR input with the dense one."""
+    a = [[True, True, False, False], [False, True, True, False], [True, False, True, True]]
+    indptr = [0, 2, 4, 7]
+    indices = [0, 1, 1, 2, 0, 2, 3]
+    bs = [[True, False, True], [True, True, True], [False, False, False]]
+    dense = [x for x, _ in solve_with_rank(a, bs)]
+    assert solve_sparse(indptr, indices, (3, 4), bs) == dense
+
+
+def test_invert() -> None:
+    """Check `a @ inv(a) == I` over GF(2)."""
+    a = [[True, True, False], [False, True, True], [False, False, True]]
+    inv = invert(a)
+    assert inv is not None
+    n = len(a)
+    for r in range(n):
+        row = [sum(a[r][k] and inv[k][c] for k in range(n)) % 2 == 1 for c in range(n)]
+        assert row == [r == c for c in range(n)]
+
+
+def test_invert_singular() -> None:
+    """Return `None` for singular and raise for non-square input."""
+    assert invert([[True, True], [True, True]]) is None
+    with pytest.raises(ValueError, match="square"):
+        invert([[True, False]])
+
+
+def test_solve_all_solutions() -> None:
+    """Enumerate the full solution set of a rank-deficient system."""
+    a = [
+        [True, True, False, False],
+        [False, True, True, False],
+        [True, False, True, False],
+    ]
+    b = [True, False, True]
+    xs = solve_all_solutions(a, b, 2)
+    assert sorted(xs) == [
+        [False, True, True, False],
+        [False, True, True, True],
+        [True, False, False, False],
+        [True, False, False, True],
+    ]
+    assert solve_all_solutions(a, [True, False, False], 2) == []
+    with pytest.raises(ValueError, match="free variables"):
+        solve_all_solutions(a, b, 1)
+
+
+def test_det_gf2() -> None:
+    """Check nonsingularity against `invert`."""
+    assert det_gf2([[True, False], [False, True]])
+    singular = [[True, True, False], [False, True, True], [True, False, True]]
+    assert not det_gf2(singular)
+    rng = random.Random(42)
+    for n in range(1, 8):
+        a = [[rng.random() < 0.5 for _ in range(n)] for _ in range(n)]
+        assert det_gf2(a) == (invert(a) is not None)
+    with pytest.raises(ValueError, match="square"):
+        det_gf2([[True, False]])
+
+
+def test_solve_int() -> None:
+    """Compare against the bool path and reject non-0/1 entries."""
+    a = [[1, 1, 0], [0, 1, 1], [1, 0, 1]]
+    bs = [[1, 0, 1], [1, 1, 1]]
+    a_bool = [[x == 1 for x in row] for row in a]
+    bs_bool = [[x == 1 for x in b] for b in bs]
+    expected = [x for x, _ in solve_with_rank(a_bool, bs_bool)]
+    assert solve_int(a, bs) == expected
+    with pytest.raises(ValueError, match="0 and 1"):
+        solve_int(a, [[1, 2, 0]])
@@ -1,7 +1,9 @@
 """Test flow."""
 
+import networkx as nx
 import pytest
 from fastflow import flow
+from fastflow._impl import flow as flow_impl
 
 from tests.assets import CASES, FlowTestCase
 
@@ -11,3 +13,132 @@ def test_flow_graphix(c: FlowTestCase) -> None:
     """Compare the results with the graphix package."""
     result = flow.find(c.g, c.iset, c.oset)
     assert result == c.flow
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_exists(c: FlowTestCase) -> None:
+    """Agree with `find`."""
+    assert flow.exists(c.g, c.iset, c.oset) == (c.flow is not None)
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_dag(c: FlowTestCase) -> None:
+    """Check that every edge goes from a later layer to an earlier one."""
+    edges = flow.dag(c.g, c.iset, c.oset)
+    if c.flow is None:
+        assert edges is None
+        return
+    assert edges is not None
+    for i, j in edges:
+        assert c.flow.layer[i] > c.flow.layer[j]
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_depth(c: FlowTestCase) -> None:
+    """Agree with the layers of `find`."""
+    expected = None if c.flow is None else max(c.flow.layer.values()) + 1
+    assert flow.depth(c.g, c.iset, c.oset) == expected
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_layers(c: FlowTestCase) -> None:
+    """Agree with the layers of `find`."""
+    result = flow.layers(c.g, c.iset, c.oset)
+    if c.flow is None:
+        assert result is None
+        return
+    assert result is not None
+    for k, nodes in enumerate(result):
+        assert nodes == {v for v, lv in c.flow.layer.items() if lv == k}
+
+
+def test_verify_all() -> None:
+    """Collect every violation of a hand-built flow."""
+    # 0 - 1 - 2 - 3 - 4
+    g = [{1}, {0, 2}, {1, 3}, {2, 4}, {3}]
+    iset = {0}
+    oset = {4}
+    assert flow_impl.verify_all({0: 1, 1: 2, 2: 3, 3: 4}, [4, 3, 2, 1, 0], g, iset, oset) == []
+    errs = flow_impl.verify_all({0: 1, 1: 2, 2: 4, 3: 4}, [4, 5, 2, 1, 0], g, iset, oset)
+    assert errs == [
+        "must be 0 -> 1: layer check failed",
+        "2 and 4 not connected: graph check failed",
+    ]
+
+
+def test_as_gflow() -> None:
+    """Wrap each successor into a singleton set."""
+    f, layer = flow_impl.as_gflow({0: 1, 1: 2}, [2, 1, 0])
+    assert f == {0: {1}, 1: {2}}
+    assert layer == [2, 1, 0]
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_or_blocker(c: FlowTestCase) -> None:
+    """Agree with `find` if a flow exists."""
+    found, blocker = flow.find_or_blocker(c.g, c.iset, c.oset)
+    if c.flow is None:
+        assert found is None
+        assert blocker in c.g.nodes
+    else:
+        assert found == c.flow
+        assert blocker is None
+
+
+def test_find_or_blocker_stalled() -> None:
+    """Report a stalled corrector if no flow exists."""
+    # a - b - e, b - c - d
+    g: nx.Graph[str] = nx.Graph([("a", "b"), ("b", "c"), ("b", "e"), ("c", "d")])
+    # b is corrected by e, but then b has two uncorrected neighbors a and c
+    assert flow.find_or_blocker(g, {"a"}, {"e"}) == (None, "b")
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_bounded(c: FlowTestCase) -> None:
+    """Agree with `find` within the bound, and fail below the true depth."""
+    assert flow.find_bounded(c.g, c.iset, c.oset, len(c.g)) == c.flow
+    if c.flow is not None:
+        depth = max(c.flow.layer.values()) + 1
+        assert flow.find_bounded(c.g, c.iset, c.oset, depth) == c.flow
+        assert flow.find_bounded(c.g, c.iset, c.oset, depth - 1) is None
+    with pytest.raises(ValueError, match="non-negative"):
+        flow.find_bounded(c.g, c.iset, c.oset, -1)
+
+
+def test_check_definition() -> None:
+    """Reject a flow violating only the definition."""
+    # 0 - 1 - 2 - 3 - 4
+    g = [{1}, {0, 2}, {1, 3}, {2, 4}, {3}]
+    f = {0: 1, 1: 2, 2: 3, 3: 4}
+    flow_impl.check_definition(f, [4, 3, 2, 1, 0], g)
+    # Domain and initial conditions hold, but 0 is not before 1
+    with pytest.raises(ValueError, match="must be 0 -> 1"):
+        flow_impl.check_definition(f, [3, 4, 2, 1, 0], g)
+
+
+def test_inverse() -> None:
+    """Round trip and reject non-injective maps."""
+    f = {0: 1, 1: 2, 2: 3, 3: 4}
+    finv = flow_impl.inverse(f)
+    assert finv == {1: 0, 2: 1, 3: 2, 4: 3}
+    assert flow_impl.inverse(finv) == f
+    with pytest.raises(ValueError, match="injective"):
+        flow_impl.inverse({0: 2, 1: 2})
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_verify_all(c: FlowTestCase) -> None:
+    """Accept the answer."""
+    if c.flow is None:
+        return
+    assert flow.verify_all(c.flow.f, c.flow.layer, c.g, c.iset, c.oset) == []
+
+
+def test_verify_all_labels() -> None:
+    """Report the violations by position in `g.nodes`."""
+    # a - b - c
+    g: nx.Graph[str] = nx.Graph([("a", "b"), ("b", "c")])
+    f = {"a": "b", "b": "c"}
+    assert flow.verify_all(f, {"a": 1, "b": 2, "c": 0}, g, {"a"}, {"c"}) == ["must be 0 -> 1: layer check failed"]
+    with pytest.raises(ValueError, match="Layer should be specified"):
+        flow.verify_all(f, {"a": 2, "b": 1}, g, {"a"}, {"c"})
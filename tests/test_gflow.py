@@ -1,7 +1,10 @@
 """Test gflow."""
 
+import networkx as nx
 import pytest
 from fastflow import gflow
+from fastflow._impl import gflow as gflow_impl
+from fastflow.common import Plane
 
 from tests.assets import CASES, FlowTestCase
 
@@ -11,3 +14,157 @@ def test_gflow_graphix(c: FlowTestCase) -> None:
     """Compare the results with the graphix package."""
     result = gflow.find(c.g, c.iset, c.oset, c.plane)
     assert result == c.gflow
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_exists(c: FlowTestCase) -> None:
+    """Agree with `find`."""
+    assert gflow.exists(c.g, c.iset, c.oset, c.plane) == (c.gflow is not None)
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_min(c: FlowTestCase) -> None:
+    """Never use more corrections than `find`, with the same layer."""
+    expected = gflow.find(c.g, c.iset, c.oset, c.plane)
+    actual = gflow.find_min(c.g, c.iset, c.oset, c.plane)
+    if expected is None:
+        assert actual is None
+        return
+    assert actual is not None
+    assert actual.layer == expected.layer
+    assert sum(map(len, actual.f.values())) <= sum(map(len, expected.f.values()))
+
+
+def test_find_min_labels() -> None:
+    """Decode the corrections with the original labels."""
+    # a - d, a - f, b - d, b - e, b - f, c - e, c - f
+    g: nx.Graph[str] = nx.Graph([("a", "d"), ("a", "f"), ("b", "d"), ("b", "e"), ("b", "f"), ("c", "e"), ("c", "f")])
+    iset = {"a", "b", "c"}
+    oset = {"d", "e", "f"}
+    expected = gflow.find(g, iset, oset)
+    actual = gflow.find_min(g, iset, oset)
+    assert expected is not None
+    assert actual is not None
+    assert actual.layer == expected.layer
+    assert set(actual.f) == iset
+    assert all(fu <= oset for fu in actual.f.values())
+    assert sum(map(len, actual.f.values())) <= sum(map(len, expected.f.values()))
+
+
+def test_find_with_local_complements() -> None:
+    """Find gflow after complementing the input."""
+    # 1 - 0 - 2
+    g = [{1, 2}, {0}, {0}]
+    plane = {0: gflow_impl.Plane.XY, 1: gflow_impl.Plane.XY}
+    assert gflow_impl.find(g, {0}, {2}, plane) is None
+    assert gflow_impl.find_with_local_complements(g, {0}, {2}, plane, 0) is None
+    ret = gflow_impl.find_with_local_complements(g, {0}, {2}, plane, 2)
+    assert ret is not None
+    seq, _, layer = ret
+    assert seq == [0]
+    assert layer[2] == 0
+    with pytest.raises(ValueError, match="max_ops too large"):
+        gflow_impl.find_with_local_complements(g, {0}, {2}, plane, 4)
+
+
+def _odd(c: FlowTestCase, nodes: set[int]) -> set[int]:
+    return {v for v in c.g.nodes if len(nodes & set(c.g[v])) % 2 == 1}
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_verbose(c: FlowTestCase) -> None:
+    """Agree with `find` and derive the rest from it."""
+    report = gflow.find_verbose(c.g, c.iset, c.oset, c.plane)
+    if c.gflow is None:
+        assert report is None
+        return
+    assert report is not None
+    assert report.f == c.gflow.f
+    assert report.layer == c.gflow.layer
+    assert report.depth == max(c.gflow.layer.values()) + 1
+    assert report.odd == {u: _odd(c, fu) for u, fu in c.gflow.f.items()}
+    for u, v in report.order:
+        assert c.gflow.layer[u] > c.gflow.layer[v]
+    assert report.rank.keys() == report.nullity.keys() == c.gflow.f.keys()
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_all(c: FlowTestCase) -> None:
+    """Start from `find` and share its layer."""
+    results = gflow.find_all(c.g, c.iset, c.oset, 4, c.plane)
+    if c.gflow is None:
+        assert results == []
+        return
+    assert results[0] == c.gflow
+    assert len({frozenset((u, frozenset(fu)) for u, fu in r.f.items()) for r in results}) == len(results)
+    for r in results:
+        assert r.layer == c.gflow.layer
+        gflow.verify(r.f, r.layer, c.g, c.iset, c.oset, c.plane)
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_multiplane(c: FlowTestCase) -> None:
+    """Agree with `find` if a single plane is allowed."""
+    plane = c.plane or dict.fromkeys(c.g.nodes - c.oset, Plane.XY)
+    result = gflow.find_multiplane(c.g, c.iset, c.oset, {u: [pu] for u, pu in plane.items()})
+    if c.gflow is None:
+        assert result is None
+    else:
+        assert result == (c.gflow, plane)
+
+
+def test_find_multiplane_choice() -> None:
+    """Fall back to the next plane if the first one does not work."""
+    # b - a - c
+    g: nx.Graph[str] = nx.Graph([("b", "a"), ("a", "c")])
+    assert gflow.find(g, {"a"}, {"c"}) is None
+    allowed = {"a": [Plane.XY], "b": [Plane.XY, Plane.YZ]}
+    result = gflow.find_multiplane(g, {"a"}, {"c"}, allowed)
+    assert result is not None
+    r, plane = result
+    assert plane == {"a": Plane.XY, "b": Plane.YZ}
+    gflow.verify(r.f, r.layer, g, {"a"}, {"c"}, plane)
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_with_forbidden_odd(c: FlowTestCase) -> None:
+    """Keep the forbidden nodes out of the odd neighbors."""
+    assert gflow.find_with_forbidden_odd(c.g, c.iset, c.oset, set(), c.plane) == c.gflow
+    result = gflow.find_with_forbidden_odd(c.g, c.iset, c.oset, c.iset, c.plane)
+    if result is None:
+        return
+    for u, fu in result.f.items():
+        assert _odd(c, fu) & c.iset <= {u}
+    gflow.verify(result.f, result.layer, c.g, c.iset, c.oset, c.plane)
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_find_csr(c: FlowTestCase) -> None:
+    """Reproduce `find` from the arrays."""
+    ret = gflow.find_csr(c.g, c.iset, c.oset, c.plane)
+    if c.gflow is None:
+        assert ret is None
+        return
+    assert ret is not None
+    indptr, indices, layer = ret
+    nodes = list(c.g.nodes)
+    assert len(indptr) == len(nodes) + 1
+    f = {nodes[i]: {nodes[j] for j in indices[indptr[i] : indptr[i + 1]]} for i in range(len(nodes))}
+    assert {u: fu for u, fu in f.items() if fu} == c.gflow.f
+    assert layer == c.gflow.layer
+
+
+@pytest.mark.parametrize("c", CASES)
+def test_verify(c: FlowTestCase) -> None:
+    """Accept the answer and reject the flattened layer."""
+    if c.gflow is None:
+        return
+    calls: list[tuple[int, int]] = []
+    gflow.verify(c.gflow.f, c.gflow.layer, c.g, c.iset, c.oset, c.plane, progress=lambda k, n: calls.append((k, n)))
+    n = len(c.gflow.f)
+    assert calls == [(k, n) for k in range(1, n + 1)]
+    if max(c.gflow.layer.values()) < 2:  # noqa: PLR2004
+        return
+    layer = {u: 0 if u in c.oset else 1 for u in c.g.nodes}
+    with pytest.raises(ValueError, match="layer check failed"):
+        gflow.verify(c.gflow.f, layer, c.g, c.iset, c.oset, c.plane)